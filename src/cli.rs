@@ -0,0 +1,131 @@
+mod commands;
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+pub use commands::run;
+
+use crate::models::Hotkey;
+
+/// Hotkeys for switching apps
+#[derive(Parser, Debug)]
+#[command(name = "groupctrl", version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    /// Keep hotkeys working without a window, see `groupctrl settings`
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// Register hotkeys apart from the window, which then may be closed
+    #[arg(long, conflicts_with = "daemon")]
+    pub headless_hotkeys: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Open the settings window of the running daemon, or the app if there is none
+    Settings {
+        #[arg(long, hide = true)]
+        from_daemon: bool, // started by the daemon, see `instance::take_over_config`
+    },
+
+    #[command(flatten)]
+    Control(ControlCommand),
+}
+
+/// Talks to the running instance, or edits `config.yaml` if there is none
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Open a group, like its hotkey would
+    Open { group: String },
+
+    /// List the groups with their hotkeys and apps
+    Groups {
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Add, remove or rename groups
+    #[command(subcommand)]
+    Group(GroupCommand),
+
+    /// Add apps to groups
+    #[command(subcommand)]
+    App(AppCommand),
+
+    /// Set the hotkey of a group, like `Super+B`
+    Bind {
+        group: String,
+        #[arg(value_parser = parse_hotkey)]
+        hotkey: Hotkey,
+    },
+
+    /// Check a config file for errors, by default the one in use
+    Validate { file: Option<PathBuf> },
+
+    /// Print where config, logs and icons are kept
+    Where,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum GroupCommand {
+    Add { name: String },
+    Rm { group: String },
+    Rename { group: String, name: String },
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum AppCommand {
+    /// Add an app by bundle id, exe path or desktop id
+    Add { group: String, app_id: String },
+}
+
+fn parse_hotkey(hotkey: &str) -> Result<Hotkey, String> {
+    Hotkey::try_from(hotkey.to_string()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+    use global_hotkey::hotkey::{Code, Modifiers};
+
+    use super::*;
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parse_settings() {
+        let cli = Cli::try_parse_from(["groupctrl", "settings"]).unwrap();
+
+        assert_eq!(cli.command, Some(Command::Settings { from_daemon: false }));
+        assert!(!cli.daemon);
+        assert!(Cli::try_parse_from(["groupctrl", "--daemon", "settings"]).is_err());
+    }
+
+    #[test]
+    fn parse_control_commands() {
+        let rename = Cli::try_parse_from(["groupctrl", "group", "rename", "Web", "Browsers"]);
+        let bind = Cli::try_parse_from(["groupctrl", "bind", "Browsers", "Ctrl+B"]);
+
+        let expected = GroupCommand::Rename {
+            group: "Web".to_string(),
+            name: "Browsers".to_string(),
+        };
+        let rename = rename.unwrap().command;
+        assert_eq!(
+            rename,
+            Some(Command::Control(ControlCommand::Group(expected)))
+        );
+        let expected = ControlCommand::Bind {
+            group: "Browsers".to_string(),
+            hotkey: Hotkey::new(Modifiers::CONTROL, Code::KeyB),
+        };
+        assert_eq!(bind.unwrap().command, Some(Command::Control(expected)));
+        assert!(Cli::try_parse_from(["groupctrl", "bind", "Browsers", "Ctrl+"]).is_err());
+    }
+}
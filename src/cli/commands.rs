@@ -0,0 +1,172 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use anyhow::{Context, bail};
+use futures::channel::mpsc::unbounded;
+
+use crate::cli::{AppCommand, ControlCommand, GroupCommand};
+use crate::instance::{self, InstanceLock};
+use crate::ipc::{self, Dispatched, GroupInfo, Reply, Request, Response};
+use crate::models::{Config, ConfigLoadError};
+use crate::os;
+use crate::services::{ConfigService, HotkeyBackend};
+
+/// Runs a command to completion, printing its outcome
+pub fn run(command: ControlCommand) -> anyhow::Result<()> {
+    let request = match command {
+        ControlCommand::Validate { file } => return validate(file),
+        ControlCommand::Where => {
+            print!("{}", format_dirs());
+            return Ok(());
+        }
+        ControlCommand::Groups { json } => {
+            let groups = read_groups()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&groups)?);
+            } else {
+                print!("{}", format_groups(&groups));
+            }
+            return Ok(());
+        }
+        ControlCommand::Open { group } => Request::OpenGroup { group },
+        ControlCommand::Group(GroupCommand::Add { name }) => Request::AddGroup { name },
+        ControlCommand::Group(GroupCommand::Rm { group }) => Request::RemoveGroup { group },
+        ControlCommand::Group(GroupCommand::Rename { group, name }) => {
+            Request::RenameGroup { group, name }
+        }
+        ControlCommand::App(AppCommand::Add { group, app_id }) => {
+            Request::AddApp { group, app: app_id }
+        }
+        ControlCommand::Bind { group, hotkey } => Request::SetHotkey {
+            group,
+            hotkey: Some(hotkey),
+        },
+    };
+    if let Reply::GroupAdded(group_id) = Target::connect()?.send(request)? {
+        println!("{group_id}");
+    }
+    Ok(())
+}
+
+/// Where edits go: the running instance if there is one, else the file,
+/// which is locked meanwhile so that no instance starts up and overwrites it
+enum Target {
+    Instance,
+    File(Box<ConfigService>, InstanceLock),
+}
+
+impl Target {
+    fn connect() -> anyhow::Result<Self> {
+        let Ok(lock) = InstanceLock::acquire(&os::lock_path())? else {
+            return Ok(Self::Instance);
+        };
+        let config = Arc::new(RwLock::new(Config::default()));
+        let (hotkey_sender, _) = unbounded();
+        let config_service = ConfigService::new(config, hotkey_sender, HotkeyBackend::Daemon);
+        if let Some(error) = config_service.load_error() {
+            bail!("could not load {}: {error}", Config::path().display());
+        }
+        Ok(Self::File(Box::new(config_service), lock))
+    }
+
+    fn send(&mut self, request: Request) -> anyhow::Result<Reply> {
+        let Self::File(config_service, _) = self else {
+            return instance::forward(request);
+        };
+        let response = match ipc::dispatch(request, config_service) {
+            Dispatched::Done(response) => response,
+            Dispatched::Execute(_) => bail!("GroupCtrl is not running"),
+        };
+        match response {
+            Response::Ok(reply) => Ok(reply),
+            Response::Error(error) => Err(error.into()),
+        }
+    }
+}
+
+/// Asks the running instance, or reads the file without migrating or
+/// quarantining it, as loading it for edits would
+fn read_groups() -> anyhow::Result<Vec<GroupInfo>> {
+    let Ok(_lock) = InstanceLock::acquire(&os::lock_path())? else {
+        let Reply::Groups(groups) = instance::forward(Request::ListGroups)? else {
+            bail!("unexpected reply to listing groups");
+        };
+        return Ok(groups);
+    };
+    let config = match Config::read_file(&Config::path()) {
+        Err(ConfigLoadError::Io(e)) if e.kind() == ErrorKind::NotFound => Config::default(),
+        result => result.with_context(|| format!("could not load {}", Config::path().display()))?,
+    };
+    Ok(ipc::list_groups(&config))
+}
+
+fn validate(file: Option<PathBuf>) -> anyhow::Result<()> {
+    let path = file.unwrap_or_else(Config::path);
+    Config::read_file(&path).with_context(|| format!("{} is invalid", path.display()))?;
+    println!("{} is valid", path.display());
+    Ok(())
+}
+
+/// One line per group, with hotkeys as `bind` takes them
+fn format_groups(groups: &[GroupInfo]) -> String {
+    groups
+        .iter()
+        .map(|group| {
+            let disabled = if group.enabled { "" } else { " (disabled)" };
+            let hotkey = group.hotkey.map(String::from).unwrap_or("-".to_string());
+            let apps = group.apps.join(", ");
+            format!("{}{disabled}\t{hotkey}\t{apps}\n", group.name)
+        })
+        .collect()
+}
+
+fn format_dirs() -> String {
+    let dirs = [
+        ("config", os::config_dir()),
+        ("logs", os::logs_dir()),
+        ("icons", os::icons_dir()),
+    ];
+    dirs.iter()
+        .map(|(name, dir)| format!("{name}\t{}\n", dir.display()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use global_hotkey::hotkey::{Code, Modifiers};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::Hotkey;
+
+    #[test]
+    fn format_groups_as_lines() {
+        // Arrange
+        let group = |name: &str, hotkey, enabled| GroupInfo {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            hotkey,
+            apps: vec![
+                "com.apple.Safari".to_string(),
+                "org.mozilla.firefox".to_string(),
+            ],
+            enabled,
+        };
+        let hotkey = Hotkey::new(Modifiers::CONTROL, Code::KeyB);
+        let groups = [
+            group("Browsers", Some(hotkey), true),
+            group("Old", None, false),
+        ];
+
+        // Act
+        let lines = format_groups(&groups);
+
+        // Assert
+        assert_eq!(
+            lines,
+            "Browsers\tCtrl+B\tcom.apple.Safari, org.mozilla.firefox\n\
+             Old (disabled)\t-\tcom.apple.Safari, org.mozilla.firefox\n"
+        );
+    }
+}
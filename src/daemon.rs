@@ -0,0 +1,220 @@
+use std::process::Command;
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use anyhow::Context;
+use dioxus::desktop::tao::event::Event;
+use dioxus::desktop::tao::event_loop::{ControlFlow, EventLoopBuilder, EventLoopProxy};
+use futures::channel::mpsc::{UnboundedReceiver, unbounded};
+use futures::executor::{block_on, block_on_stream};
+use log::{error, info};
+
+use crate::ipc::{self, Call, Dispatched, IpcClient, IpcError, Reply, Request, Response};
+use crate::models::{Action, Config, Hotkey};
+use crate::os;
+use crate::services::{
+    ActionService, CHORD_TIMEOUT, ConfigReader, ConfigService, ConfigWatcher, HotkeyBackend,
+    start_event_loop,
+};
+
+/// What wakes up the event loop of the daemon
+enum DaemonEvent {
+    Hotkey(Hotkey, Action),
+    ExpireChord(u64),
+    ConfigChanged,
+    Ipc(Call),
+    SettingsClosed,
+}
+
+/// Keeps hotkeys and the activation observer alive without a window. The
+/// event loop runs on the main thread, which macOS requires for both.
+pub fn run() -> ! {
+    start_event_loop();
+    let event_loop = EventLoopBuilder::<DaemonEvent>::with_user_event().build();
+    let proxy = event_loop.create_proxy();
+
+    let config = Arc::new(RwLock::new(Config::default()));
+    let action_service = ActionService::new(ConfigReader::new(config.clone()));
+    let (hotkey_sender, hotkey_receiver) = unbounded();
+    forward(hotkey_receiver, &proxy, |(hotkey, action)| {
+        DaemonEvent::Hotkey(hotkey, action)
+    });
+    let mut daemon = Daemon {
+        config_service: ConfigService::new(config, hotkey_sender, HotkeyBackend::Headless),
+        action_service,
+        proxy: proxy.clone(),
+        settings: None,
+    };
+    if let Some(error) = daemon.config_service.load_error() {
+        // Hotkeys stay unbound until the file is fixed, which the watcher picks up
+        error!("running without hotkeys, as the config could not be loaded: {error}");
+        daemon.show_settings(); // which shows the error
+    }
+
+    let (changed_sender, changed_receiver) = unbounded();
+    forward(changed_receiver, &proxy, |()| DaemonEvent::ConfigChanged);
+    let watcher = ConfigWatcher::new(changed_sender)
+        .inspect_err(|e| error!("could not watch config: {e}"))
+        .ok();
+
+    let (call_sender, call_receiver) = unbounded();
+    forward(call_receiver, &proxy, DaemonEvent::Ipc);
+    ipc::serve(&os::socket_path(), call_sender)
+        .unwrap_or_else(|e| error!("could not listen for requests: {e}"));
+
+    info!("running as daemon");
+    event_loop.run(move |event, _, control_flow| {
+        let _watcher = &watcher; // lives as long as the loop
+        *control_flow = ControlFlow::Wait;
+        if let Event::UserEvent(event) = event {
+            daemon.handle(event);
+        }
+    })
+}
+
+/// Wakes up the event loop for each message, from a thread of its own
+fn forward<T: Send + 'static>(
+    receiver: UnboundedReceiver<T>,
+    proxy: &EventLoopProxy<DaemonEvent>,
+    into_event: impl Fn(T) -> DaemonEvent + Send + 'static,
+) {
+    let proxy = proxy.clone();
+    thread::spawn(move || {
+        for message in block_on_stream(receiver) {
+            if proxy.send_event(into_event(message)).is_err() {
+                break; // the event loop is gone
+            }
+        }
+    });
+}
+
+struct Daemon {
+    config_service: ConfigService,
+    action_service: ActionService,
+    proxy: EventLoopProxy<DaemonEvent>,
+    settings: Option<u32>, // the pid of the settings window, while it is open
+}
+
+impl Daemon {
+    /// Like `AppActions` in the UI, which handles these while there is a window
+    fn handle(&mut self, event: DaemonEvent) {
+        match event {
+            DaemonEvent::Hotkey(hotkey, action) => {
+                if hotkey.is_chord() {
+                    self.config_service.end_chord();
+                }
+                self.execute(action);
+            }
+            DaemonEvent::ExpireChord(id) => self.config_service.expire_chord(id),
+            DaemonEvent::ConfigChanged => {
+                if !self.config_service.config().matches_file() {
+                    self.reload(); // edited in the settings window or by hand
+                }
+            }
+            DaemonEvent::Ipc(Call {
+                request: Request::HandOverConfig { pid },
+                reply,
+            }) => {
+                let _ = reply.send(self.hand_over_config(pid).into());
+            }
+            DaemonEvent::Ipc(Call { request, reply }) => {
+                match ipc::dispatch(request, &mut self.config_service) {
+                    Dispatched::Done(response) => {
+                        let _ = reply.send(response);
+                    }
+                    Dispatched::Execute(Action::ShowGroupCtrlWindow) => {
+                        let result = self.open_settings().map(|()| Reply::Done);
+                        let result = result.map_err(|e| IpcError::Failed {
+                            message: format!("{e:#}"),
+                        });
+                        let _ = reply.send(result.into());
+                    }
+                    Dispatched::Execute(action) => {
+                        let service = self.action_service.clone();
+                        thread::spawn(move || {
+                            let _ = reply.send(block_on(ipc::execute(&service, &action)));
+                        });
+                    }
+                }
+            }
+            DaemonEvent::SettingsClosed => {
+                self.settings = None;
+                self.config_service.set_handed_over(false);
+                self.reload(); // in case the watcher missed its last save
+            }
+        }
+    }
+
+    /// Stops writing the config while the settings window writes it, which only
+    /// the window started by `open_settings` may, so that there is one writer
+    fn hand_over_config(&mut self, pid: u32) -> Result<Reply, IpcError> {
+        if self.settings != Some(pid) {
+            return Err(IpcError::InvalidRequest {
+                message: "only the settings window started by the daemon takes over the config"
+                    .to_string(),
+            });
+        }
+        info!("handing over the config to the settings window");
+        self.config_service.set_handed_over(true);
+        Ok(Reply::Done)
+    }
+
+    fn execute(&mut self, action: Action) {
+        match action {
+            Action::StartChord { leader } => match self.config_service.start_chord(leader) {
+                Ok(id) => {
+                    let proxy = self.proxy.clone();
+                    thread::spawn(move || {
+                        thread::sleep(CHORD_TIMEOUT);
+                        let _ = proxy.send_event(DaemonEvent::ExpireChord(id));
+                    });
+                }
+                Err(error) => error!("could not start chord {leader}: {error}"),
+            },
+            Action::ShowGroupCtrlWindow => self.show_settings(),
+            Action::ReloadConfig => self.reload(),
+            Action::SwitchProfile { profile_id } => self.config_service.switch_profile(profile_id),
+            // Errors only go to the log, even those the window shows in a toast,
+            // as there is nothing to show them in
+            action => {
+                let service = self.action_service.clone();
+                thread::spawn(move || block_on(service.execute(&action)));
+            }
+        }
+    }
+
+    fn reload(&mut self) {
+        if let Err(error) = self.config_service.reload() {
+            error!("could not reload config, keeping the current one unsaved: {error}");
+        }
+    }
+
+    fn show_settings(&mut self) {
+        self.open_settings()
+            .unwrap_or_else(|e| error!("could not show settings window: {e:#}"));
+    }
+
+    /// Starts `groupctrl settings`, or raises its window if it's still open
+    fn open_settings(&mut self) -> anyhow::Result<()> {
+        if self.settings.is_some() {
+            info!("raising settings window");
+            let mut client = IpcClient::connect(&os::settings_socket_path())
+                .context("settings window does not answer")?;
+            return match client.send(&Request::ShowWindow)? {
+                Response::Ok(_) => Ok(()),
+                Response::Error(error) => Err(error.into()),
+            };
+        }
+        let exe = std::env::current_exe()?;
+        let mut child = Command::new(exe)
+            .args(["settings", "--from-daemon"])
+            .spawn()?;
+        self.settings = Some(child.id());
+        let proxy = self.proxy.clone();
+        thread::spawn(move || {
+            let _ = child.wait();
+            let _ = proxy.send_event(DaemonEvent::SettingsClosed);
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::bail;
+
+use crate::cli::{Cli, Command};
+use crate::ipc::{IpcClient, Reply, Request, Response};
+use crate::os;
+
+const CONNECT_ATTEMPTS: u32 = 30; // the instance may still be starting up
+const CONNECT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Held by the one process that registers hotkeys and writes the config.
+/// The OS releases it when that process exits, so a crash leaves no stale lock.
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Fails with the pid of the process holding the lock, if known
+    pub fn acquire(path: &Path) -> io::Result<Result<Self, Option<u32>>> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        match file.try_lock() {
+            Ok(()) => {
+                // Only informative, as a pid left behind by a crash is ignored
+                file.set_len(0)?;
+                write!(file, "{}", std::process::id())?;
+                Ok(Ok(Self { _file: file }))
+            }
+            Err(TryLockError::WouldBlock) => Ok(Err(read_pid(&mut file))),
+            Err(TryLockError::Error(e)) => Err(e),
+        }
+    }
+}
+
+/// Locks the instance, or passes the launch on to the one already running and exits,
+/// see `forwarded`. Runs before logging is set up, so that a second launch can't
+/// truncate the log.
+pub fn claim_or_forward(cli: &Cli) -> Option<InstanceLock> {
+    let pid = match InstanceLock::acquire(&os::lock_path()) {
+        Ok(Ok(lock)) => return Some(lock),
+        Ok(Err(pid)) => pid,
+        Err(e) => {
+            eprintln!(
+                "could not lock {}, running anyway: {e}",
+                os::lock_path().display()
+            );
+            return None;
+        }
+    };
+    let running = match pid {
+        Some(pid) => format!("GroupCtrl is already running as pid {pid}"),
+        None => "GroupCtrl is already running".to_string(),
+    };
+    let Some(request) = forwarded(cli) else {
+        eprintln!("{running}, quit it to start the daemon");
+        std::process::exit(1);
+    };
+    match forward(request) {
+        Ok(_) => std::process::exit(0),
+        Err(e) => {
+            eprintln!("{running}, but could not reach it: {e:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Asks the daemon, which holds the lock, to stop writing the config while this
+/// settings window is open. Exits if it refuses, as it only hands the config over
+/// to the window it started.
+pub fn take_over_config() {
+    let request = Request::HandOverConfig {
+        pid: std::process::id(),
+    };
+    if let Err(e) = forward(request) {
+        eprintln!("could not take over the config from the daemon: {e:#}");
+        std::process::exit(1);
+    }
+}
+
+/// What a launch asks of the running instance. Subcommands like `open` always
+/// talk to it, see `cli::run`, so only launches of the window are left.
+fn forwarded(cli: &Cli) -> Option<Request> {
+    match cli.command {
+        _ if cli.daemon => None, // the running instance already binds the hotkeys
+        None | Some(Command::Settings { .. }) => Some(Request::ShowWindow),
+        Some(Command::Control(_)) => unreachable!("run without locking"),
+    }
+}
+
+/// Sends a request to the instance holding the lock
+pub fn forward(request: Request) -> anyhow::Result<Reply> {
+    let mut attempts = 1;
+    let mut client = loop {
+        match IpcClient::connect(&os::socket_path()) {
+            Ok(client) => break client,
+            Err(_) if attempts < CONNECT_ATTEMPTS => {
+                attempts += 1;
+                thread::sleep(CONNECT_INTERVAL);
+            }
+            Err(e) => bail!("it does not answer at {}: {e}", os::socket_path().display()),
+        }
+    };
+    match client.send(&request)? {
+        Response::Ok(reply) => Ok(reply),
+        Response::Error(error) => Err(error.into()),
+    }
+}
+
+fn read_pid(file: &mut File) -> Option<u32> {
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?; // fails on Windows, where the lock is mandatory
+    content.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn lock_is_exclusive_until_dropped() {
+        // Arrange
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("groupctrl.lock");
+        let lock = InstanceLock::acquire(&path).unwrap().unwrap();
+
+        // Act
+        let second = InstanceLock::acquire(&path).unwrap();
+        drop(lock);
+        let third = InstanceLock::acquire(&path).unwrap();
+
+        // Assert
+        let holder = second.unwrap_err();
+        #[cfg(unix)] // Windows doesn't let others read the locked file
+        assert_eq!(holder, Some(std::process::id()));
+        assert!(third.is_ok());
+    }
+
+    #[test]
+    fn forward_window_launches() {
+        // Arrange
+        let launch = |args: &[&str]| Cli::try_parse_from(args).unwrap();
+
+        // Act
+        let plain = forwarded(&launch(&["groupctrl"]));
+        let settings = forwarded(&launch(&["groupctrl", "settings"]));
+        let daemon = forwarded(&launch(&["groupctrl", "--daemon"]));
+
+        // Assert
+        assert_eq!(plain, Some(Request::ShowWindow));
+        assert_eq!(settings, Some(Request::ShowWindow));
+        assert_eq!(daemon, None);
+    }
+
+    #[test]
+    fn lock_ignores_pid_left_by_crash() {
+        // Arrange
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("groupctrl.lock");
+        std::fs::write(&path, "4294967295").unwrap();
+
+        // Act
+        let lock = InstanceLock::acquire(&path).unwrap();
+
+        // Assert
+        assert!(lock.is_ok());
+        let pid = std::process::id().to_string();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), pid);
+    }
+}
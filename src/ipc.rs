@@ -0,0 +1,28 @@
+mod client;
+mod dispatch;
+mod protocol;
+mod server;
+
+use std::io;
+use std::path::Path;
+
+pub use client::IpcClient;
+pub use dispatch::{Dispatched, dispatch, execute, list_groups};
+use interprocess::local_socket::Name;
+use interprocess::local_socket::prelude::*;
+pub use protocol::{GroupInfo, IpcError, Reply, Request, Response};
+pub use server::{Call, serve};
+
+/// Named pipes can't live in a directory, so on Windows the path only names the pipe
+#[cfg(windows)]
+fn socket_name(path: &Path) -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericNamespaced;
+    let name = path.to_string_lossy().replace('\\', "/");
+    name.to_ns_name::<GenericNamespaced>()
+}
+
+#[cfg(not(windows))]
+fn socket_name(path: &Path) -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericFilePath;
+    path.to_path_buf().to_fs_name::<GenericFilePath>()
+}
@@ -0,0 +1,34 @@
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use anyhow::{Context, bail};
+use interprocess::local_socket::Stream;
+use interprocess::local_socket::prelude::*;
+
+use crate::ipc::protocol::{Request, Response};
+use crate::ipc::socket_name;
+
+/// Talks to a running instance, see `serve`
+pub struct IpcClient {
+    reader: BufReader<Stream>,
+}
+
+impl IpcClient {
+    pub fn connect(path: &Path) -> std::io::Result<Self> {
+        let stream = Stream::connect(socket_name(path)?)?;
+        Ok(Self {
+            reader: BufReader::new(stream),
+        })
+    }
+
+    pub fn send(&mut self, request: &Request) -> anyhow::Result<Response> {
+        let mut json = serde_json::to_string(request)?;
+        json.push('\n');
+        self.reader.get_mut().write_all(json.as_bytes())?;
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            bail!("GroupCtrl hung up without answering");
+        }
+        serde_json::from_str(&line).context("could not parse response")
+    }
+}
@@ -0,0 +1,354 @@
+use uuid::Uuid;
+
+use crate::ipc::protocol::{GroupInfo, IpcError, Reply, Request, Response};
+use crate::models::{Action, Config, Identifiable};
+use crate::os::{App, AppQuery, System};
+use crate::services::{ActionService, ConfigService};
+
+/// What's left of a request once the config service did its part
+#[derive(Debug)]
+pub enum Dispatched {
+    Done(Response),
+    Execute(Action), // may take a while or need the UI, so the caller decides where to run it
+}
+
+/// Handles requests on the config right away, leaving actions to `execute`
+pub fn dispatch(request: Request, config_service: &mut ConfigService) -> Dispatched {
+    try_dispatch(request, config_service)
+        .unwrap_or_else(|error| Dispatched::Done(Response::Error(error)))
+}
+
+fn try_dispatch(
+    request: Request,
+    config_service: &mut ConfigService,
+) -> Result<Dispatched, IpcError> {
+    let reply = match request {
+        Request::OpenGroup { group } => {
+            let group_id = find_group(&config_service.config(), &group)?;
+            return Ok(Dispatched::Execute(Action::OpenGroup { group_id }));
+        }
+        Request::ListGroups => Reply::Groups(list_groups(&config_service.config())),
+        Request::AddGroup { name } => {
+            check_saveable(config_service)?;
+            if config_service
+                .config()
+                .groups()
+                .iter()
+                .any(|g| g.name == name)
+            {
+                return Err(IpcError::DuplicateGroupName { name });
+            }
+            let group_id = config_service.add_group(name);
+            check_saved(config_service)?;
+            Reply::GroupAdded(group_id)
+        }
+        Request::RemoveGroup { group } => {
+            let group_id = find_group(&config_service.config(), &group)?;
+            check_saveable(config_service)?;
+            config_service.remove_group(group_id);
+            check_saved(config_service)?;
+            Reply::Done
+        }
+        Request::RenameGroup { group, name } => {
+            let group_id = find_group(&config_service.config(), &group)?;
+            check_saveable(config_service)?;
+            config_service
+                .set_name(group_id, name.clone())
+                .map_err(|_| IpcError::DuplicateGroupName { name })?;
+            check_saved(config_service)?;
+            Reply::Done
+        }
+        Request::AddApp { group, app } => {
+            let group_id = find_group(&config_service.config(), &group)?;
+            check_saveable(config_service)?;
+            config_service.add_app(group_id, App::from(app));
+            check_saved(config_service)?;
+            Reply::Done
+        }
+        Request::SetHotkey { group, hotkey } => {
+            let group_id = find_group(&config_service.config(), &group)?;
+            check_saveable(config_service)?;
+            config_service
+                .set_hotkey(group_id, hotkey)
+                .map_err(IpcError::Hotkey)?;
+            check_saved(config_service)?;
+            Reply::Done
+        }
+        Request::CurrentApp => Reply::CurrentApp(System::current_app().map_err(failed)?),
+        Request::ShowWindow => return Ok(Dispatched::Execute(Action::ShowGroupCtrlWindow)),
+        Request::HandOverConfig { .. } => {
+            return Err(IpcError::InvalidRequest {
+                message: "only the daemon hands over the config".to_string(),
+            });
+        }
+        Request::ReloadConfig => {
+            config_service.reload().map_err(|error| IpcError::Config {
+                message: error.to_string(),
+            })?;
+            Reply::Done
+        }
+    };
+    Ok(Dispatched::Done(Response::Ok(reply)))
+}
+
+/// Runs what `dispatch` left over
+pub async fn execute(action_service: &ActionService, action: &Action) -> Response {
+    let result = action_service.try_execute(action).await;
+    result.map(|()| Reply::Done).map_err(failed).into()
+}
+
+fn find_group(config: &Config, group: &str) -> Result<Uuid, IpcError> {
+    config
+        .find_group(group)
+        .map(|g| g.id())
+        .ok_or_else(|| IpcError::UnknownGroup {
+            group: group.to_string(),
+        })
+}
+
+/// Edits wouldn't be saved while the file has errors or another process
+/// writes it, see `ConfigService::save`
+fn check_saveable(config_service: &ConfigService) -> Result<(), IpcError> {
+    if config_service.is_handed_over() {
+        return Err(IpcError::HandedOver);
+    }
+    match config_service.load_error() {
+        Some(error) => Err(IpcError::Config {
+            message: error.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Edits are kept in memory after a failed save, but the caller should know
+fn check_saved(config_service: &ConfigService) -> Result<(), IpcError> {
+    match config_service.save_error() {
+        Some(error) => Err(IpcError::Failed {
+            message: format!("could not save config: {error}"),
+        }),
+        None => Ok(()),
+    }
+}
+
+pub fn list_groups(config: &Config) -> Vec<GroupInfo> {
+    config
+        .groups()
+        .iter()
+        .map(|group| GroupInfo {
+            id: group.id(),
+            name: group.name.clone(),
+            hotkey: group.hotkey,
+            apps: group.apps().iter().map(|app| app.id()).collect(),
+            enabled: config.is_enabled(group.id()),
+        })
+        .collect()
+}
+
+fn failed(error: anyhow::Error) -> IpcError {
+    IpcError::Failed {
+        message: format!("{error:#}"),
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::{Arc, RwLock};
+    use std::thread;
+
+    use futures::StreamExt;
+    use futures::channel::mpsc::{UnboundedReceiver, unbounded};
+    use futures::executor::block_on;
+    use global_hotkey::hotkey::{Code, Modifiers};
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::ipc::{Call, IpcClient, serve};
+    use crate::models::Hotkey;
+    use crate::services::{ConfigReader, HotkeyBackend, HotkeyBindError};
+
+    const SAFARI: &str = "com.apple.Safari";
+
+    struct Setup {
+        _dir: TempDir,
+        path: PathBuf,
+        calls: UnboundedReceiver<Call>,
+        config_service: ConfigService,
+        action_service: ActionService,
+    }
+
+    fn setup() -> Setup {
+        let dir = tempfile::tempdir().unwrap();
+        System::set_config_dir(dir.path().to_path_buf());
+        let config = Arc::new(RwLock::new(Config::default()));
+        let action_service = ActionService::new(ConfigReader::new(config.clone()));
+        let (hotkey_sender, _) = unbounded();
+        let config_service = ConfigService::new(config, hotkey_sender, HotkeyBackend::Daemon);
+        let path = dir.path().join("groupctrl.sock");
+        let (call_sender, calls) = unbounded();
+        serve(&path, call_sender).unwrap();
+        Setup {
+            _dir: dir,
+            path,
+            calls,
+            config_service,
+            action_service,
+        }
+    }
+
+    /// Sends from another thread and answers on this one, where the sim state lives
+    fn send(setup: &mut Setup, request: Request) -> Response {
+        let path = setup.path.clone();
+        let client =
+            thread::spawn(move || IpcClient::connect(&path).unwrap().send(&request).unwrap());
+        let Call { request, reply } = block_on(setup.calls.next()).unwrap();
+        let response = match dispatch(request, &mut setup.config_service) {
+            Dispatched::Done(response) => response,
+            Dispatched::Execute(action) => block_on(execute(&setup.action_service, &action)),
+        };
+        reply.send(response).unwrap();
+        client.join().unwrap()
+    }
+
+    fn set_hotkey(group: &str, hotkey: Hotkey) -> Request {
+        Request::SetHotkey {
+            group: group.to_string(),
+            hotkey: Some(hotkey),
+        }
+    }
+
+    #[test]
+    fn add_app_then_list_groups() {
+        // Arrange
+        let mut setup = setup();
+        let group_id = setup.config_service.add_group("Browsers".to_string());
+        let add_app = Request::AddApp {
+            group: "Browsers".to_string(),
+            app: SAFARI.to_string(),
+        };
+
+        // Act
+        let added = send(&mut setup, add_app);
+        let listed = send(&mut setup, Request::ListGroups);
+
+        // Assert
+        assert_eq!(added, Response::Ok(Reply::Done));
+        let Response::Ok(Reply::Groups(groups)) = listed else {
+            panic!("unexpected response {listed:?}");
+        };
+        assert_eq!((groups[0].id, groups[0].enabled), (group_id, true));
+        assert_eq!(groups[0].apps, vec![SAFARI.to_string()]);
+    }
+
+    #[test]
+    fn open_group_by_id() {
+        // Arrange
+        let mut setup = setup();
+        System::install(SAFARI);
+        let group_id = setup.config_service.add_group("Browsers".to_string());
+        setup
+            .config_service
+            .add_app(group_id, App::from(SAFARI.to_string()));
+
+        // Act
+        let group = group_id.to_string();
+        let response = send(&mut setup, Request::OpenGroup { group });
+
+        // Assert
+        assert_eq!(response, Response::Ok(Reply::Done));
+        assert_eq!(System::frontmost().as_deref(), Some(SAFARI));
+    }
+
+    #[test]
+    fn set_hotkey_conflict() {
+        // Arrange
+        let mut setup = setup();
+        setup.config_service.add_group("Fst".to_string());
+        setup.config_service.add_group("Snd".to_string());
+        let hotkey = Hotkey::new(Modifiers::META, Code::KeyB);
+        send(&mut setup, set_hotkey("Fst", hotkey));
+
+        // Act
+        let response = send(&mut setup, set_hotkey("Snd", hotkey));
+
+        // Assert
+        let conflict = "open group 'Fst'".to_string();
+        assert_eq!(
+            response,
+            Response::Error(IpcError::Hotkey(HotkeyBindError::Conflict {
+                hotkey,
+                conflict
+            }))
+        );
+    }
+
+    #[test]
+    fn add_then_rename_group() {
+        // Arrange
+        let mut setup = setup();
+        setup.config_service.add_group("Editors".to_string());
+        let add_group = Request::AddGroup {
+            name: "Web".to_string(),
+        };
+        let rename = |name: &str| Request::RenameGroup {
+            group: "Web".to_string(),
+            name: name.to_string(),
+        };
+
+        // Act
+        let added = send(&mut setup, add_group);
+        let duplicate = send(&mut setup, rename("Editors"));
+        let renamed = send(&mut setup, rename("Browsers"));
+
+        // Assert
+        let group_id = setup
+            .config_service
+            .config()
+            .find_group("Browsers")
+            .unwrap()
+            .id();
+        assert_eq!(added, Response::Ok(Reply::GroupAdded(group_id)));
+        assert_eq!(
+            duplicate,
+            Response::Error(IpcError::DuplicateGroupName {
+                name: "Editors".to_string()
+            })
+        );
+        assert_eq!(renamed, Response::Ok(Reply::Done));
+    }
+
+    #[test]
+    fn edits_fail_while_handed_over() {
+        // Arrange
+        let mut setup = setup();
+        setup.config_service.set_handed_over(true);
+        let add_group = Request::AddGroup {
+            name: "Browsers".to_string(),
+        };
+
+        // Act
+        let response = send(&mut setup, add_group);
+
+        // Assert
+        assert_eq!(response, Response::Error(IpcError::HandedOver));
+        assert!(setup.config_service.config().groups().is_empty());
+    }
+
+    #[test]
+    fn unknown_group() {
+        // Arrange
+        let mut setup = setup();
+        let group = "Editors".to_string();
+
+        // Act
+        let response = send(&mut setup, Request::OpenGroup { group });
+
+        // Assert
+        assert_eq!(
+            response,
+            Response::Error(IpcError::UnknownGroup {
+                group: "Editors".to_string()
+            })
+        );
+    }
+}
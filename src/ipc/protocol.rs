@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::models::Hotkey;
+use crate::services::HotkeyBindError;
+
+/// One line of JSON, like `{"request": "open_group", "group": "Browsers"}`.
+/// Groups are referred to by name or id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum Request {
+    OpenGroup {
+        group: String,
+    },
+    ListGroups,
+    AddGroup {
+        name: String,
+    },
+    RemoveGroup {
+        group: String,
+    },
+    RenameGroup {
+        group: String,
+        name: String,
+    },
+    AddApp {
+        group: String,
+        app: String, // bundle id, exe path or desktop id
+    },
+    SetHotkey {
+        group: String,
+        hotkey: Option<Hotkey>, // `null` unbinds the group
+    },
+    CurrentApp,
+    ReloadConfig,
+    ShowWindow, // sent by a second launch before it exits
+    HandOverConfig {
+        pid: u32, // of the settings window the daemon started, which then writes the config
+    },
+}
+
+/// Answers each request with one line, either `{"ok": ...}` or `{"error": ...}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Response {
+    Ok(Reply),
+    Error(IpcError),
+}
+
+impl From<Result<Reply, IpcError>> for Response {
+    fn from(result: Result<Reply, IpcError>) -> Self {
+        match result {
+            Ok(reply) => Response::Ok(reply),
+            Err(error) => Response::Error(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reply {
+    Done,
+    Groups(Vec<GroupInfo>),
+    GroupAdded(Uuid),
+    CurrentApp(Option<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub hotkey: Option<Hotkey>,
+    pub apps: Vec<String>,
+    pub enabled: bool, // part of the active profile
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IpcError {
+    #[error("invalid request: {message}")]
+    InvalidRequest { message: String },
+
+    #[error("no group is named or has the id '{group}'")]
+    UnknownGroup { group: String },
+
+    #[error("there already is a group named '{name}'")]
+    DuplicateGroupName { name: String },
+
+    #[error(transparent)]
+    Hotkey(HotkeyBindError),
+
+    #[error("the config has errors, fix them and reload it: {message}")]
+    Config { message: String },
+
+    #[error("the settings window is editing the config, make the change there or close it")]
+    HandedOver,
+
+    #[error("{message}")]
+    Failed { message: String },
+
+    #[error("GroupCtrl stopped before answering")]
+    Unavailable,
+}
+
+#[cfg(test)]
+mod tests {
+    use global_hotkey::hotkey::{Code, Modifiers};
+
+    use super::*;
+
+    #[test]
+    fn parse_request() {
+        let line = r#"{"request": "set_hotkey", "group": "Browsers", "hotkey": "Ctrl+B"}"#;
+
+        let request: Request = serde_json::from_str(line).unwrap();
+
+        let hotkey = Hotkey::new(Modifiers::CONTROL, Code::KeyB);
+        assert_eq!(
+            request,
+            Request::SetHotkey {
+                group: "Browsers".to_string(),
+                hotkey: Some(hotkey)
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_hotkey_error() {
+        let hotkey = Hotkey::new(Modifiers::CONTROL, Code::KeyB);
+        let error = IpcError::Hotkey(HotkeyBindError::Invalid { hotkey });
+
+        let json = serde_json::to_string(&Response::Error(error)).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"error":{"kind":"hotkey","reason":"invalid","hotkey":"Ctrl+B"}}"#
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+
+use dioxus::hooks::UnboundedSender;
+use interprocess::local_socket::prelude::*;
+use interprocess::local_socket::{Listener, ListenerOptions, Stream};
+use log::{info, warn};
+
+use crate::ipc::protocol::{IpcError, Request, Response};
+use crate::ipc::socket_name;
+
+/// A request from a client, answered through `reply` by whoever owns the services
+#[derive(Debug, Clone)]
+pub struct Call {
+    pub request: Request,
+    pub reply: mpsc::Sender<Response>,
+}
+
+/// Listens at `path` on a thread of its own, passing each request on as a `Call`
+pub fn serve(path: &Path, call_sender: UnboundedSender<Call>) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let listener = listen(path)?;
+    info!("listening for requests at {}", path.display());
+    thread::Builder::new()
+        .name("ipc".to_string())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        warn!("could not accept connection: {e}");
+                        continue;
+                    }
+                };
+                let call_sender = call_sender.clone();
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, &call_sender) {
+                        warn!("connection failed: {e}");
+                    }
+                });
+            }
+        })?;
+    Ok(())
+}
+
+/// Takes over the socket if no one answers on it, e.g. after a crash
+fn listen(path: &Path) -> io::Result<Listener> {
+    let options = ListenerOptions::new().name(socket_name(path)?);
+    match options.create_sync() {
+        Err(e)
+            if e.kind() == io::ErrorKind::AddrInUse
+                && Stream::connect(socket_name(path)?).is_err() =>
+        {
+            warn!("replacing stale socket at {}", path.display());
+            ListenerOptions::new()
+                .name(socket_name(path)?)
+                .try_overwrite(true)
+                .create_sync()
+        }
+        result => result,
+    }
+}
+
+/// Answers one line with one line, until the client hangs up
+fn handle_connection(stream: Stream, call_sender: &UnboundedSender<Call>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        if !line.trim().is_empty() {
+            let response = match serde_json::from_str(&line) {
+                Ok(request) => forward(request, call_sender),
+                Err(e) => Response::Error(IpcError::InvalidRequest {
+                    message: e.to_string(),
+                }),
+            };
+            let mut json = serde_json::to_string(&response)?;
+            json.push('\n');
+            reader.get_mut().write_all(json.as_bytes())?;
+        }
+        line.clear();
+    }
+    Ok(())
+}
+
+fn forward(request: Request, call_sender: &UnboundedSender<Call>) -> Response {
+    let (reply, response) = mpsc::channel();
+    let call = Call { request, reply };
+    if call_sender.unbounded_send(call).is_err() {
+        return Response::Error(IpcError::Unavailable);
+    }
+    response
+        .recv()
+        .unwrap_or(Response::Error(IpcError::Unavailable))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc::unbounded;
+
+    use super::*;
+    use crate::ipc::IpcClient;
+    use crate::ipc::protocol::Request;
+
+    #[test]
+    fn invalid_request() {
+        // Arrange
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.sock");
+        let (call_sender, _calls) = unbounded();
+        serve(&path, call_sender).unwrap();
+        let stream = Stream::connect(socket_name(&path).unwrap()).unwrap();
+        let mut reader = BufReader::new(stream);
+
+        // Act
+        reader
+            .get_mut()
+            .write_all(b"{\"request\": \"fly\"}\n")
+            .unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+
+        // Assert
+        let response: Response = serde_json::from_str(&line).unwrap();
+        assert!(
+            matches!(response, Response::Error(IpcError::InvalidRequest { .. })),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn unavailable_without_receiver() {
+        // Arrange
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.sock");
+        let (call_sender, calls) = unbounded();
+        serve(&path, call_sender).unwrap();
+        drop(calls);
+
+        // Act
+        let response = IpcClient::connect(&path)
+            .unwrap()
+            .send(&Request::ListGroups)
+            .unwrap();
+
+        // Assert
+        assert_eq!(response, Response::Error(IpcError::Unavailable));
+    }
+}
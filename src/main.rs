@@ -1,16 +1,23 @@
+mod cli;
 #[allow(dead_code, unused_imports)]
 mod components;
+mod daemon;
+mod instance;
+mod ipc;
 mod models;
 mod os;
 mod services;
 mod ui;
 mod util;
 
+use clap::Parser;
 use dioxus::desktop::{Config, LogicalSize, WindowBuilder};
 use dioxus::prelude::*;
 use simplelog::*;
 
+use crate::cli::{Cli, Command};
 use crate::os::{AppQuery, System};
+use crate::services::HotkeyBackend;
 use crate::ui::Root;
 
 const FONT_URL: &str = "https://fonts.googleapis.com/css2?family=Inter:ital,opsz,wght@0,14..32,100..900;1,14..32,100..900&display=swap";
@@ -19,7 +26,7 @@ const FONT_URL: &str = "https://fonts.googleapis.com/css2?family=Inter:ital,opsz
 pub static PREVIOUS_APP: std::sync::OnceLock<String> = std::sync::OnceLock::new();
 
 #[cfg(debug_assertions)]
-fn setup_logging() -> anyhow::Result<()> {
+fn setup_logging(_log_name: &str) -> anyhow::Result<()> {
     TermLogger::init(
         LevelFilter::Debug,
         ConfigBuilder::new().build(),
@@ -29,16 +36,34 @@ fn setup_logging() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Each process logs to a file of its own, as the daemon outlives the window
 #[cfg(not(debug_assertions))]
-fn setup_logging() -> anyhow::Result<()> {
+fn setup_logging(log_name: &str) -> anyhow::Result<()> {
     std::fs::create_dir_all(os::logs_dir())?;
-    let log_file = std::fs::File::create(os::logs_dir().join("app.log"))?;
+    let log_file = std::fs::File::create(os::logs_dir().join(format!("{log_name}.log")))?;
     WriteLogger::init(LevelFilter::Info, ConfigBuilder::new().build(), log_file)?;
     Ok(())
 }
 
 fn main() {
-    setup_logging().expect("Logging setup failed");
+    let cli = Cli::parse();
+    if let Some(Command::Control(command)) = cli.command {
+        if let Err(error) = cli::run(command) {
+            eprintln!("error: {error:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    let from_daemon = matches!(cli.command, Some(Command::Settings { from_daemon: true }));
+    let _instance_lock = if from_daemon {
+        instance::take_over_config(); // the daemon holds the lock
+        None
+    } else {
+        instance::claim_or_forward(&cli)
+    };
+
+    let log_name = if cli.daemon { "daemon" } else { "app" };
+    setup_logging(log_name).expect("Logging setup failed");
 
     if os::icons_dir().exists() {
         let _ = std::fs::remove_dir_all(os::icons_dir());
@@ -49,12 +74,16 @@ fn main() {
         std::process::exit(1);
     }));
 
+    if cli.daemon {
+        daemon::run();
+    }
+
     #[cfg(target_os = "macos")]
     let head = format!(
         r#"<link rel="stylesheet" href="{FONT_URL}"><link rel="stylesheet" href="{}">"#,
         asset!("/assets/tailwind.css")
     );
-    #[cfg(target_os = "windows")]
+    #[cfg(not(target_os = "macos"))]
     let head = format!(
         r#"<link rel="stylesheet" href="{FONT_URL}"><style>{}</style>"#,
         include_str!("../assets/tailwind.css")
@@ -82,7 +111,17 @@ fn main() {
         builder.with_title("GroupCtrl")
     };
 
+    let hotkey_backend = if from_daemon {
+        HotkeyBackend::Daemon
+    } else if cli.headless_hotkeys {
+        services::start_event_loop(); // before Dioxus claims the hotkey events
+        HotkeyBackend::Headless
+    } else {
+        HotkeyBackend::Window // also for `settings` without a daemon, which it replaces
+    };
+
     LaunchBuilder::desktop()
         .with_cfg(Config::new().with_window(window).with_custom_head(head))
+        .with_context(hotkey_backend)
         .launch(Root);
 }
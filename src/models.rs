@@ -1,12 +1,23 @@
 mod action;
+mod app_entry;
+mod app_matcher;
+mod binding;
 mod config;
 mod config_persistence;
+mod gesture;
 mod group;
 mod hotkey;
 mod hotkey_conversion;
+mod profile;
 mod traits;
 
-pub use action::{Action, Bindable};
+pub use action::{Action, Bindable, Direction, SequenceStep};
+pub use app_matcher::AppMatcher;
+pub use binding::Binding;
 pub use config::{Config, DuplicateGroupName};
+pub use config_persistence::{Backup, CURRENT_VERSION, ConfigLoadError};
+pub use gesture::{Gesture, GestureTiming};
+pub use group::{CycleOrder, RepeatPolicy, ReverseHotkey};
 pub use hotkey::Hotkey;
+pub use profile::Profile;
 pub use traits::Identifiable;
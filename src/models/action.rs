@@ -1,22 +1,157 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::models::app_entry::AppEntry;
 use crate::models::group::Group;
-use crate::models::{Config, Hotkey, Identifiable};
+use crate::models::{Config, Gesture, Hotkey, Identifiable};
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
 pub enum Action {
-    OpenGroup { group_id: Uuid },
+    OpenGroup {
+        group_id: Uuid,
+    },
+    #[serde(skip)] // bound through the group instead
+    CycleGroup {
+        group_id: Uuid,
+        direction: Direction,
+    },
+    OpenTarget {
+        group_id: Uuid,
+    },
+    LaunchAllApps {
+        group_id: Uuid,
+    },
+    QuitAllApps {
+        group_id: Uuid,
+    },
+    LaunchApp {
+        app: Box<AppEntry>, // may only be known on other platforms, like apps of groups
+    },
+    FocusPreviousApp,
+    OpenUrl {
+        url: String,
+    },
+    RunCommand {
+        argv: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cwd: Option<PathBuf>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        env: BTreeMap<String, String>,
+    },
+    ShowGroupCtrlWindow,
+    ReloadConfig,
+    SwitchProfile {
+        #[serde(default)] // all groups
+        profile_id: Option<Uuid>,
+    },
+    #[serde(skip)] // bound for the leader of chords
+    StartChord {
+        leader: Hotkey,
+    },
+    Sequence {
+        steps: Vec<SequenceStep>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        stop_on_error: bool,
+    },
+}
+
+/// One action of a sequence, optionally waiting before it runs
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SequenceStep {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+    pub action: Action,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    Forward,
+    Backward,
 }
 
 impl Action {
     pub fn describe(&self, config: &Config) -> String {
+        // Continuation of "Hotkey is already bound to..."
         match self {
             Action::OpenGroup { group_id } => {
-                // Continuation of "Hotkey is already bound to..."
                 format!("open group '{}'", config.group(*group_id).unwrap().name)
             }
+            Action::CycleGroup {
+                group_id,
+                direction,
+            } => {
+                let name = &config.group(*group_id).unwrap().name;
+                match direction {
+                    Direction::Forward => format!("cycle through group '{name}'"),
+                    Direction::Backward => format!("cycle backwards through group '{name}'"),
+                }
+            }
+            Action::OpenTarget { group_id } => {
+                let name = &config.group(*group_id).unwrap().name;
+                format!("open the target of group '{name}'")
+            }
+            Action::LaunchAllApps { group_id } => {
+                let name = &config.group(*group_id).unwrap().name;
+                format!("launch all apps of group '{name}'")
+            }
+            Action::QuitAllApps { group_id } => {
+                let name = &config.group(*group_id).unwrap().name;
+                format!("quit all apps of group '{name}'")
+            }
+            Action::LaunchApp { app } => match app.app() {
+                Some(app) => format!("launch '{app}'"),
+                None => "launch an app of another platform".to_string(),
+            },
+            Action::FocusPreviousApp => "focus the previous app".to_string(),
+            Action::OpenUrl { url } => format!("open '{url}'"),
+            Action::RunCommand { argv, .. } => format!("run '{}'", argv.join(" ")),
+            Action::ShowGroupCtrlWindow => "show the GroupCtrl window".to_string(),
+            Action::ReloadConfig => "reload the config".to_string(),
+            Action::SwitchProfile { profile_id } => {
+                match profile_id.and_then(|id| config.profiles().iter().find(|p| p.id() == id)) {
+                    Some(profile) => format!("switch to profile '{}'", profile.name),
+                    None => "enable all groups".to_string(),
+                }
+            }
+            Action::StartChord { leader } => format!("start chords with {leader}"),
+            Action::Sequence { steps, .. } => format!("run a sequence of {} actions", steps.len()),
+        }
+    }
+
+    /// Whether the action acts on the group or profile with this id
+    pub fn references(&self, id: Uuid) -> bool {
+        match self {
+            Action::SwitchProfile { profile_id } => *profile_id == Some(id),
+            action => action.group_id() == Some(id),
         }
     }
+
+    pub fn group_id(&self) -> Option<Uuid> {
+        match self {
+            Action::OpenGroup { group_id }
+            | Action::CycleGroup { group_id, .. }
+            | Action::OpenTarget { group_id }
+            | Action::LaunchAllApps { group_id }
+            | Action::QuitAllApps { group_id } => Some(*group_id),
+            _ => None,
+        }
+    }
+
+    /// Whether the action acts on GroupCtrl itself, so the UI has to handle it
+    pub fn targets_group_ctrl(&self) -> bool {
+        matches!(
+            self,
+            Action::ShowGroupCtrlWindow
+                | Action::ReloadConfig
+                | Action::SwitchProfile { .. }
+                | Action::StartChord { .. }
+        )
+    }
 }
 
 pub trait Bindable {
@@ -31,3 +166,31 @@ impl Bindable for Group {
         (self.hotkey, action)
     }
 }
+
+impl Group {
+    /// Walks the group backwards, unless the reverse hotkey is disabled
+    pub fn reverse_binding(&self) -> (Option<Hotkey>, Action) {
+        let action = Action::CycleGroup {
+            group_id: self.id(),
+            direction: Direction::Backward,
+        };
+        (self.reverse_hotkey.resolve(self.hotkey), action)
+    }
+
+    /// Double press and hold of the group hotkey, if gestures are enabled
+    pub fn gesture_bindings(&self) -> [(Option<Hotkey>, Action); 2] {
+        let group_id = self.id();
+        let hotkey = |gesture| {
+            self.hotkey
+                .filter(|_| self.gestures)
+                .map(|hk| hk.with_gesture(gesture))
+        };
+        [
+            (
+                hotkey(Gesture::DoublePress),
+                Action::OpenTarget { group_id },
+            ),
+            (hotkey(Gesture::Hold), Action::LaunchAllApps { group_id }),
+        ]
+    }
+}
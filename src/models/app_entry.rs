@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::app_matcher::{AppMatcher, MatcherFormat};
+use crate::os::App;
+
+/// An app as written in the config, which may only be known on other platforms,
+/// or a rule that matches apps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "AppEntryFormat", into = "AppEntryFormat")]
+pub struct AppEntry {
+    format: AppEntryFormat,
+    app: Option<App>,                            // resolved for the current platform
+    matcher: Option<Result<AppMatcher, String>>, // compiled rule, errors fail validation
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum AppEntryFormat {
+    Id(String), // identifier for the current platform
+    Matcher(MatcherFormat),
+    Platforms(PlatformIds),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PlatformIds {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mac: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    windows: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    linux: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>, // for humans reading the config
+}
+
+impl PlatformIds {
+    fn current(&self) -> Option<&String> {
+        if cfg!(target_os = "macos") {
+            self.mac.as_ref()
+        } else if cfg!(target_os = "windows") {
+            self.windows.as_ref()
+        } else {
+            self.linux.as_ref()
+        }
+    }
+}
+
+impl AppEntry {
+    /// `None` if the entry has no identifier for the current platform
+    pub fn app(&self) -> Option<&App> {
+        self.app.as_ref()
+    }
+
+    /// `None` unless the entry is a valid rule
+    pub fn matcher(&self) -> Option<&AppMatcher> {
+        self.matcher.as_ref()?.as_ref().ok()
+    }
+
+    /// The pattern and why it doesn't compile, if the entry is a broken rule
+    pub(super) fn matcher_error(&self) -> Option<(&str, &str)> {
+        let (AppEntryFormat::Matcher(format), Some(Err(error))) = (&self.format, &self.matcher)
+        else {
+            return None;
+        };
+        Some((format.source(), error))
+    }
+}
+
+impl PartialEq for AppEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.format == other.format // the rest is derived from it
+    }
+}
+
+impl Eq for AppEntry {}
+
+impl From<App> for AppEntry {
+    fn from(app: App) -> Self {
+        Self {
+            format: AppEntryFormat::Id(app.clone().into()),
+            app: Some(app),
+            matcher: None,
+        }
+    }
+}
+
+impl From<AppEntryFormat> for AppEntry {
+    fn from(format: AppEntryFormat) -> Self {
+        let id = match &format {
+            AppEntryFormat::Id(id) => Some(id),
+            AppEntryFormat::Matcher(_) => None,
+            AppEntryFormat::Platforms(ids) => ids.current(),
+        };
+        let app = id.cloned().map(App::from);
+        let matcher = match &format {
+            AppEntryFormat::Matcher(matcher) => Some(AppMatcher::compile(matcher)),
+            _ => None,
+        };
+        Self {
+            format,
+            app,
+            matcher,
+        }
+    }
+}
+
+impl From<AppEntry> for AppEntryFormat {
+    fn from(entry: AppEntry) -> Self {
+        entry.format
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Identifiable;
+
+    const CURRENT: &str = if cfg!(target_os = "macos") {
+        "mac"
+    } else if cfg!(target_os = "windows") {
+        "windows"
+    } else {
+        "linux"
+    };
+
+    #[test]
+    fn parse_plain_id() {
+        let entry: AppEntry = serde_yaml::from_str("com.example.app").unwrap();
+
+        assert_eq!(
+            entry.app().map(|app| app.id()).as_deref(),
+            Some("com.example.app")
+        );
+    }
+
+    const OTHER: &str = if cfg!(target_os = "macos") {
+        "linux"
+    } else {
+        "mac"
+    };
+
+    #[test]
+    fn parse_current_platform() {
+        let content = format!("{{ {OTHER}: x.app, {CURRENT}: com.example.app, name: App }}");
+
+        let entry: AppEntry = serde_yaml::from_str(&content).unwrap();
+
+        assert_eq!(
+            entry.app().map(|app| app.id()).as_deref(),
+            Some("com.example.app")
+        );
+    }
+
+    #[test]
+    fn parse_rule() {
+        let content = "glob: com.jetbrains.*\n";
+
+        let entry: AppEntry = serde_yaml::from_str(content).unwrap();
+
+        assert!(entry.app().is_none());
+        assert!(entry.matcher().is_some());
+        assert_eq!(serde_yaml::to_string(&entry).unwrap(), content);
+    }
+
+    #[test]
+    fn keep_other_platforms() {
+        let content = format!("{OTHER}: x.app\nname: Other\n");
+
+        let entry: AppEntry = serde_yaml::from_str(&content).unwrap();
+
+        assert!(entry.app().is_none());
+        assert_eq!(serde_yaml::to_string(&entry).unwrap(), content);
+    }
+}
@@ -0,0 +1,137 @@
+use std::fmt::{Display, Formatter};
+
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::Identifiable;
+use crate::os::{App, AppMetadata};
+
+/// A rule for group membership, written with either `glob` or `regex`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct MatcherFormat {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    glob: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    regex: Option<String>,
+    #[serde(default, skip_serializing_if = "MatchField::is_default")]
+    field: MatchField,
+}
+
+/// What a pattern is matched against
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchField {
+    #[default]
+    Id, // bundle id, exe path or desktop id
+    Name,
+}
+
+impl MatchField {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AppMatcher {
+    pattern: Pattern,
+    field: MatchField,
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl AppMatcher {
+    pub(super) fn compile(format: &MatcherFormat) -> Result<Self, String> {
+        let pattern = match (&format.glob, &format.regex) {
+            (Some(glob), None) => GlobBuilder::new(glob)
+                .case_insensitive(cfg!(target_os = "windows")) // like paths there
+                .build()
+                .map(|glob| Pattern::Glob(glob.compile_matcher()))
+                .map_err(|error| error.kind().to_string())?,
+            (None, Some(regex)) => Regex::new(regex)
+                .map(Pattern::Regex)
+                .map_err(|error| error.to_string())?,
+            _ => return Err("a rule needs either a glob or a regex".to_string()),
+        };
+        Ok(Self {
+            pattern,
+            field: format.field,
+        })
+    }
+
+    pub fn matches(&self, app: &App) -> bool {
+        let id = app.id();
+        let subject = match self.field {
+            MatchField::Id => id.as_str(),
+            MatchField::Name => app.name(),
+        };
+        match &self.pattern {
+            Pattern::Glob(glob) => glob.is_match(subject),
+            Pattern::Regex(regex) => regex.is_match(subject),
+        }
+    }
+}
+
+impl Display for AppMatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let field = match self.field {
+            MatchField::Id => "Apps",
+            MatchField::Name => "App names",
+        };
+        match &self.pattern {
+            Pattern::Glob(glob) => write!(f, "{field} like '{}'", glob.glob()),
+            Pattern::Regex(regex) => write!(f, "{field} matching '{regex}'"),
+        }
+    }
+}
+
+impl MatcherFormat {
+    /// The pattern as written, for error messages
+    pub(super) fn source(&self) -> &str {
+        self.glob
+            .as_ref()
+            .or(self.regex.as_ref())
+            .map_or("", String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(content: &str) -> Result<AppMatcher, String> {
+        AppMatcher::compile(&serde_yaml::from_str(content).unwrap())
+    }
+
+    #[test]
+    fn glob_matches_id() {
+        let matcher = matcher("glob: com.jetbrains.*").unwrap();
+
+        assert!(matcher.matches(&App::from("com.jetbrains.intellij".to_string())));
+        assert!(!matcher.matches(&App::from("com.apple.Safari".to_string())));
+    }
+
+    #[test]
+    fn regex_matches_name() {
+        let app = App::from("com.example.Notes".to_string());
+        let pattern = regex::escape(app.name());
+
+        let matcher = matcher(&format!("regex: '^{pattern}$'\nfield: name")).unwrap();
+
+        assert!(matcher.matches(&app));
+        assert!(!matcher.matches(&App::from("com.example.Other".to_string())));
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let error = matcher("regex: '(unclosed'").unwrap_err();
+
+        assert!(error.contains("unclosed"), "{error}");
+    }
+}
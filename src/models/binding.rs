@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Action, Bindable, Gesture, Hotkey};
+
+/// A hotkey that triggers an action on its own, rather than opening a group
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Binding {
+    pub hotkey: Hotkey,
+    #[serde(default, skip_serializing_if = "Gesture::is_press")]
+    pub gesture: Gesture,
+    pub action: Action,
+}
+
+impl Bindable for Binding {
+    fn binding(&self) -> (Option<Hotkey>, Action) {
+        (
+            Some(self.hotkey.with_gesture(self.gesture)),
+            self.action.clone(),
+        )
+    }
+}
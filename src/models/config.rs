@@ -9,26 +9,148 @@ pub struct DuplicateGroupName;
 
 use crate::models::group::Group;
 use crate::models::hotkey::Hotkey;
-use crate::models::{Action, Bindable, Identifiable};
+use crate::models::{
+    Action, Bindable, Binding, CURRENT_VERSION, CycleOrder, GestureTiming, Identifiable, Profile,
+    RepeatPolicy, ReverseHotkey,
+};
 use crate::os::App;
 
-#[derive(Default, Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
+    version: u64, // older files are migrated before deserializing
     groups: Vec<Group>,
-    // settings: Settings as enum (can implement Actionable)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bindings: Vec<Binding>, // standalone, not tied to a group
+    #[serde(default, skip_serializing_if = "GestureTiming::is_default")]
+    gesture_timing: GestureTiming,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    profiles: Vec<Profile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active_profile: Option<Uuid>, // all groups are enabled without one
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            groups: Vec::new(),
+            bindings: Vec::new(),
+            gesture_timing: GestureTiming::default(),
+            profiles: Vec::new(),
+            active_profile: None,
+        }
+    }
 }
 
 impl Config {
+    /// Bindings of the enabled groups followed by standalone ones, where derived
+    /// reverse hotkeys and gestures give way to hotkeys that were set explicitly
     pub fn bindings(&self) -> Vec<(Hotkey, Action)> {
-        self.groups
+        self.bindings_of(|group_id| self.is_enabled(group_id))
+    }
+
+    fn bindings_of(&self, enabled: impl Fn(Uuid) -> bool) -> Vec<(Hotkey, Action)> {
+        let groups: Vec<&Group> = self.groups.iter().filter(|g| enabled(g.id())).collect();
+        let explicit: Vec<Hotkey> = self.explicit_hotkeys(&groups).collect();
+        let unclaimed = |hotkey: Option<Hotkey>| {
+            hotkey.filter(|hk| !explicit.iter().any(|other| other.conflicts_with(*hk)))
+        };
+        groups
             .iter()
-            .filter_map(|group| {
+            .flat_map(|group| {
                 let (hotkey, action) = group.binding();
-                Some((hotkey?, action))
+                let (reverse_hotkey, reverse_action) = group.reverse_binding();
+                let reverse_hotkey = match group.reverse_hotkey {
+                    ReverseHotkey::Derived => unclaimed(reverse_hotkey),
+                    _ => reverse_hotkey,
+                };
+                let gestures = group
+                    .gesture_bindings()
+                    .map(|(hotkey, action)| (unclaimed(hotkey), action));
+                [(hotkey, action), (reverse_hotkey, reverse_action)]
+                    .into_iter()
+                    .chain(gestures)
             })
+            .chain(self.bindings.iter().map(Bindable::binding))
+            .filter_map(|(hotkey, action)| Some((hotkey?, action)))
             .collect()
     }
 
+    /// Bindings of the profiles the group belongs to, as if each was active, and
+    /// the active ones, for checking hotkeys of groups outside the active profile.
+    /// A group in no profile is only enabled along with all others.
+    pub fn profile_bindings(&self, group_id: Uuid) -> Vec<(Hotkey, Action)> {
+        let profiles: Vec<_> = self
+            .profiles
+            .iter()
+            .filter(|profile| profile.groups.contains(&group_id))
+            .collect();
+        let inactive = if profiles.is_empty() {
+            self.bindings_of(|_| true)
+        } else {
+            profiles
+                .iter()
+                .flat_map(|profile| self.bindings_of(|id| profile.groups.contains(&id)))
+                .collect()
+        };
+        inactive.into_iter().chain(self.bindings()).collect()
+    }
+
+    /// The effective reverse binding of a group, as if it was enabled, see `bindings`
+    pub fn reverse_binding(&self, group_id: Uuid) -> anyhow::Result<(Option<Hotkey>, Action)> {
+        let (reverse_hotkey, action) = self.group(group_id)?.reverse_binding();
+        let bound = self
+            .bindings_of(|id| id == group_id || self.is_enabled(id))
+            .into_iter()
+            .any(|binding| binding.1 == action);
+        Ok((reverse_hotkey.filter(|_| bound), action))
+    }
+
+    fn explicit_hotkeys<'a>(&'a self, groups: &[&'a Group]) -> impl Iterator<Item = Hotkey> {
+        let group_hotkeys = groups.iter().flat_map(|group| {
+            let custom = match group.reverse_hotkey {
+                ReverseHotkey::Custom(hotkey) => Some(hotkey),
+                _ => None,
+            };
+            group.hotkey.into_iter().chain(custom)
+        });
+        group_hotkeys.chain(self.bindings.iter().filter_map(|b| b.binding().0))
+    }
+
+    pub fn gesture_timing(&self) -> GestureTiming {
+        self.gesture_timing
+    }
+
+    pub fn profiles(&self) -> &Vec<Profile> {
+        &self.profiles
+    }
+
+    pub fn active_profile_id(&self) -> Option<Uuid> {
+        self.active_profile
+    }
+
+    pub fn active_profile(&self) -> Option<&Profile> {
+        let id = self.active_profile?;
+        self.profiles.iter().find(|p| p.id() == id)
+    }
+
+    fn active_profile_mut(&mut self) -> Option<&mut Profile> {
+        let id = self.active_profile?;
+        self.profiles.iter_mut().find(|p| p.id() == id)
+    }
+
+    /// `None` enables all groups
+    pub fn set_active_profile(&mut self, profile_id: Option<Uuid>) {
+        self.active_profile = profile_id;
+    }
+
+    /// Whether the group belongs to the active profile, if there is one
+    pub fn is_enabled(&self, group_id: Uuid) -> bool {
+        self.active_profile()
+            .is_none_or(|profile| profile.groups.contains(&group_id))
+    }
+
     pub fn groups(&self) -> &Vec<Group> {
         &self.groups
     }
@@ -37,11 +159,32 @@ impl Config {
         let group = Group::new(name);
         let group_id = group.id();
         self.groups.push(group);
+        if let Some(profile) = self.active_profile_mut() {
+            profile.groups.push(group_id); // so it can be bound right away
+        }
         group_id
     }
 
     pub fn remove_group(&mut self, group_id: Uuid) {
-        self.groups.retain(|g| g.id() != group_id)
+        self.groups.retain(|g| g.id() != group_id);
+        for profile in &mut self.profiles {
+            profile.groups.retain(|id| *id != group_id);
+        }
+        self.remove_references(group_id);
+    }
+
+    /// Drops standalone bindings and sequence steps that act on a removed group
+    /// or profile, as they would fail validation on the next load
+    fn remove_references(&mut self, id: Uuid) {
+        for binding in &mut self.bindings {
+            if let Action::Sequence { steps, .. } = &mut binding.action {
+                steps.retain(|step| !step.action.references(id));
+            }
+        }
+        self.bindings.retain(|binding| match &binding.action {
+            Action::Sequence { steps, .. } => !steps.is_empty(),
+            action => !action.references(id),
+        });
     }
 
     pub fn group(&self, group_id: Uuid) -> anyhow::Result<&Group> {
@@ -51,6 +194,14 @@ impl Config {
             .with_context(|| format!("group {} not found", group_id))
     }
 
+    /// By id or by name, as scripts refer to groups
+    pub fn find_group(&self, reference: &str) -> Option<&Group> {
+        let id = Uuid::parse_str(reference).ok();
+        self.groups
+            .iter()
+            .find(|g| Some(g.id()) == id || g.name == reference)
+    }
+
     fn group_mut(&mut self, group_id: Uuid) -> anyhow::Result<&mut Group> {
         self.groups
             .iter_mut()
@@ -74,8 +225,24 @@ impl Config {
         self.group_mut(group_id).unwrap().hotkey = hotkey;
     }
 
+    pub fn set_cycle(&mut self, group_id: Uuid, cycle: CycleOrder) {
+        self.group_mut(group_id).unwrap().cycle = cycle;
+    }
+
+    pub fn set_on_repeat(&mut self, group_id: Uuid, on_repeat: RepeatPolicy) {
+        self.group_mut(group_id).unwrap().on_repeat = on_repeat;
+    }
+
+    pub fn set_gestures(&mut self, group_id: Uuid, gestures: bool) {
+        self.group_mut(group_id).unwrap().gestures = gestures;
+    }
+
+    pub fn set_reverse_hotkey(&mut self, group_id: Uuid, reverse_hotkey: ReverseHotkey) {
+        self.group_mut(group_id).unwrap().reverse_hotkey = reverse_hotkey;
+    }
+
     pub fn set_target(&mut self, group_id: Uuid, app: Option<App>) {
-        self.group_mut(group_id).unwrap().target = app;
+        self.group_mut(group_id).unwrap().set_target(app);
     }
 
     pub fn add_app(&mut self, group_id: Uuid, app: App) {
@@ -86,3 +253,160 @@ impl Config {
         self.group_mut(group_id).unwrap().remove_app(app_id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use global_hotkey::hotkey::{Code, Modifiers};
+
+    use super::*;
+    use crate::models::Gesture;
+
+    #[test]
+    fn find_group_by_id_or_name() {
+        let mut config = Config::default();
+        let group_id = config.add_group("Browsers".to_string());
+
+        let by_id = config.find_group(&group_id.to_string()).map(|g| g.id());
+        let by_name = config.find_group("Browsers").map(|g| g.id());
+
+        assert_eq!((by_id, by_name), (Some(group_id), Some(group_id)));
+        assert!(config.find_group("Editors").is_none());
+    }
+
+    #[test]
+    fn bindings_include_derived_reverse() {
+        let mut config = Config::default();
+        let group_id = config.add_group("Browsers".to_string());
+        let hotkey = Hotkey::new(Modifiers::META, Code::KeyB);
+        config.set_hotkey(group_id, Some(hotkey));
+
+        let bindings = config.bindings();
+
+        let reverse = config.group(group_id).unwrap().reverse_binding();
+        assert_eq!(bindings.len(), 2);
+        assert_eq!(bindings[1], (hotkey.with_shift().unwrap(), reverse.1));
+    }
+
+    #[test]
+    fn bindings_prefer_explicit_over_derived() {
+        let mut config = Config::default();
+        let fst = config.add_group("Fst".to_string());
+        let snd = config.add_group("Snd".to_string());
+        let hotkey = Hotkey::new(Modifiers::META, Code::KeyB);
+        config.set_hotkey(fst, Some(hotkey));
+        config.set_hotkey(snd, hotkey.with_shift());
+
+        let bindings = config.bindings();
+
+        assert_eq!(bindings.len(), 2); // Snd already uses Shift, so it has no reverse
+        assert_eq!(config.reverse_binding(fst).unwrap().0, None);
+    }
+
+    #[test]
+    fn bindings_include_standalone() {
+        let mut config = Config::default();
+        let group_id = config.add_group("Browsers".to_string());
+        let hotkey = Hotkey::new(Modifiers::META, Code::KeyB);
+        config.set_hotkey(group_id, Some(hotkey));
+        let shifted = hotkey.with_shift().unwrap();
+        config.bindings.push(Binding {
+            hotkey: shifted,
+            gesture: Gesture::Press,
+            action: Action::ReloadConfig,
+        });
+
+        let bindings = config.bindings();
+
+        assert_eq!(bindings.len(), 2); // the standalone binding replaces the derived reverse
+        assert_eq!(bindings[1], (shifted, Action::ReloadConfig));
+    }
+
+    #[test]
+    fn bindings_include_unclaimed_gestures() {
+        let mut config = Config::default();
+        let group_id = config.add_group("Browsers".to_string());
+        let hotkey = Hotkey::new(Modifiers::META, Code::KeyB);
+        config.set_hotkey(group_id, Some(hotkey));
+        config.set_gestures(group_id, true);
+        config.bindings.push(Binding {
+            hotkey,
+            gesture: Gesture::Hold,
+            action: Action::ReloadConfig,
+        });
+
+        let bindings = config.bindings();
+
+        let double_press = hotkey.with_gesture(Gesture::DoublePress);
+        assert_eq!(bindings.len(), 4); // the standalone binding replaces the derived hold
+        assert_eq!(bindings[2], (double_press, Action::OpenTarget { group_id }));
+        assert_eq!(
+            bindings[3],
+            (hotkey.with_gesture(Gesture::Hold), Action::ReloadConfig)
+        );
+    }
+
+    #[test]
+    fn bindings_only_include_enabled_groups() {
+        let mut config = Config::default();
+        let work = config.add_group("Work".to_string());
+        let home = config.add_group("Home".to_string());
+        let hotkey = Hotkey::new(Modifiers::META, Code::KeyB);
+        config.set_hotkey(work, Some(hotkey));
+        config.set_hotkey(home, Some(hotkey));
+        let profile: Profile =
+            serde_yaml::from_str(&format!("name: At Work\ngroups: [{work}]")).unwrap();
+        config.set_active_profile(Some(profile.id()));
+        config.profiles.push(profile);
+
+        let bindings = config.bindings();
+
+        assert_eq!(bindings[0], (hotkey, Action::OpenGroup { group_id: work }));
+        assert!(!config.is_enabled(home));
+        assert_eq!(bindings.len(), 2);
+    }
+
+    #[test]
+    fn profile_bindings_of_disabled_group() {
+        let mut config = Config::default();
+        let work = config.add_group("Work".to_string());
+        let home = config.add_group("Home".to_string());
+        let hotkey = Hotkey::new(Modifiers::META, Code::KeyB);
+        config.set_hotkey(home, Some(hotkey));
+        let profile: Profile =
+            serde_yaml::from_str(&format!("name: At Work\ngroups: [{work}]")).unwrap();
+        config.set_active_profile(Some(profile.id()));
+        config.profiles.push(profile);
+        let profile: Profile =
+            serde_yaml::from_str(&format!("name: At Home\ngroups: [{home}]")).unwrap();
+        config.profiles.push(profile);
+
+        let bindings = config.profile_bindings(home);
+
+        assert!(!config.is_enabled(home));
+        assert_eq!(bindings[0], (hotkey, Action::OpenGroup { group_id: home }));
+        assert!(
+            config
+                .profile_bindings(work)
+                .iter()
+                .all(|(hk, _)| *hk != hotkey)
+        );
+    }
+
+    #[test]
+    fn profile_bindings_of_group_in_no_profile() {
+        let mut config = Config::default();
+        let work = config.add_group("Work".to_string());
+        let other = config.add_group("Other".to_string());
+        let hotkey = Hotkey::new(Modifiers::META, Code::KeyB);
+        config.set_hotkey(work, Some(hotkey));
+        let profile: Profile =
+            serde_yaml::from_str(&format!("name: At Work\ngroups: [{work}]")).unwrap();
+        config.set_active_profile(Some(profile.id()));
+        config.profiles.push(profile);
+
+        let bindings = config.profile_bindings(other);
+
+        assert!(!config.is_enabled(other));
+        assert!(bindings.contains(&(hotkey, Action::OpenGroup { group_id: work })));
+    }
+}
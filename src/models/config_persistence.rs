@@ -1,25 +1,574 @@
-use std::path::PathBuf;
+mod backup;
+mod error;
+mod migration;
 
-use super::Config;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+pub use backup::Backup;
+pub use error::ConfigLoadError;
+use log::{info, warn};
+pub use migration::CURRENT_VERSION;
+use serde_yaml::Value;
+
+use super::{Action, Config, Hotkey, Identifiable};
 
 const CONFIG_FILE_NAME: &str = "config.yaml";
+const QUARANTINE_FILE_NAME: &str = "config.broken.yaml";
+const BACKUPS_DIR_NAME: &str = "backups";
+const MAX_BACKUPS: usize = 20;
 
 impl Config {
-    fn path() -> PathBuf {
+    pub fn path() -> PathBuf {
         crate::os::config_dir().join(CONFIG_FILE_NAME)
     }
 
-    pub fn load() -> anyhow::Result<Self> {
-        let content = std::fs::read_to_string(Self::path())?;
-        Ok(serde_yaml::from_str(&content)?)
+    pub fn quarantine_path() -> PathBuf {
+        crate::os::config_dir().join(QUARANTINE_FILE_NAME)
+    }
+
+    /// A missing file yields the default config, while a broken one is
+    /// copied to the quarantine path so it can't get lost on the next save
+    pub fn load() -> Result<Self, ConfigLoadError> {
+        let content = match std::fs::read_to_string(Self::path()) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error.into()),
+        };
+        let (config, migrated_from) = Self::from_content(&content).inspect_err(|error| {
+            if error.is_quarantined() {
+                Self::quarantine(&content);
+            }
+        })?;
+        if let Some(version) = migrated_from {
+            // Keep the original, as older builds can't read the migrated file
+            let backup_path = Self::migration_backup_path(version);
+            std::fs::write(&backup_path, &content)?;
+            info!(
+                "migrated config from version {version} to {CURRENT_VERSION}, backup at {}",
+                backup_path.display()
+            );
+            if let Err(error) = config.save() {
+                warn!("could not save migrated config: {error}");
+            }
+        }
+        Ok(config)
+    }
+
+    /// Reads a file like `load` would, but never writes: older files are only
+    /// migrated in memory, and broken ones aren't quarantined
+    pub fn read_file(path: &Path) -> Result<Self, ConfigLoadError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_content(&content).map(|(config, _)| config)
+    }
+
+    /// Lists backups of previously saved configs, newest first
+    pub fn backups() -> std::io::Result<Vec<Backup>> {
+        backup::list(&Self::backups_dir())
+    }
+
+    /// Replaces the saved config with a backup, which is migrated if necessary
+    pub fn restore_backup(backup: &Backup) -> Result<Self, ConfigLoadError> {
+        let content = std::fs::read_to_string(&backup.path)?;
+        let (config, _) = Self::from_content(&content)?;
+        config.save().map_err(std::io::Error::other)?;
+        info!("restored config from {}", backup.path.display());
+        Ok(config)
+    }
+
+    /// Also returns the version the content was migrated from, if it was older
+    fn from_content(content: &str) -> Result<(Self, Option<u64>), ConfigLoadError> {
+        match Self::version(content)? {
+            version if version > CURRENT_VERSION => Err(ConfigLoadError::TooNew { version }),
+            CURRENT_VERSION => Ok((Self::parse(content)?, None)),
+            version => Ok((Self::migrate(content, version)?, Some(version))),
+        }
+    }
+
+    /// Upgrades an older document step by step
+    fn migrate(content: &str, version: u64) -> Result<Self, ConfigLoadError> {
+        let mut document: Value = serde_yaml::from_str(content)
+            .map_err(|error| ConfigLoadError::from_yaml(error, content))?;
+        migration::migrate(&mut document, version);
+        let migrated = serde_yaml::to_string(&document).expect("YAML values serialize");
+        Self::parse(&migrated)
+    }
+
+    fn version(content: &str) -> Result<u64, ConfigLoadError> {
+        let document: Value = serde_yaml::from_str(content)
+            .map_err(|error| ConfigLoadError::from_yaml(error, content))?;
+        migration::version(&document)
+    }
+
+    fn migration_backup_path(version: u64) -> PathBuf {
+        crate::os::config_dir().join(format!("config.v{version}.yaml"))
+    }
+
+    fn backups_dir() -> PathBuf {
+        crate::os::config_dir().join(BACKUPS_DIR_NAME)
     }
 
+    /// Replaces the file atomically, backing up the previous version first
     pub fn save(&self) -> anyhow::Result<()> {
-        if let Some(parent) = Self::path().parent() {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
         let content = serde_yaml::to_string(self)?;
-        std::fs::write(Self::path(), content)?;
+        match std::fs::read_to_string(&path) {
+            Ok(previous) if previous == content => return Ok(()),
+            Ok(previous) => backup::create(&Self::backups_dir(), &previous, MAX_BACKUPS)
+                .unwrap_or_else(|error| warn!("could not back up config: {error}")),
+            Err(_) => {}
+        }
+        write_atomically(&path, &content)?;
+        Ok(())
+    }
+
+    /// Whether the file holds exactly what `save` would write, e.g. after our own save
+    pub fn matches_file(&self) -> bool {
+        let content = std::fs::read_to_string(Self::path());
+        content.is_ok_and(|content| serde_yaml::to_string(self).is_ok_and(|own| own == content))
+    }
+
+    fn parse(content: &str) -> Result<Self, ConfigLoadError> {
+        let config: Self = serde_yaml::from_str(content)
+            .map_err(|error| ConfigLoadError::from_yaml(error, content))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigLoadError> {
+        let mut names = HashSet::new();
+        let mut ids = HashSet::new();
+        for group in self.groups() {
+            if !names.insert(&group.name) {
+                return Err(ConfigLoadError::DuplicateGroupName {
+                    name: group.name.clone(),
+                });
+            }
+            if !ids.insert(group.id()) {
+                return Err(ConfigLoadError::DuplicateGroupId { id: group.id() });
+            }
+            if let Some((pattern, message)) = group.matcher_errors().next() {
+                return Err(ConfigLoadError::InvalidRule {
+                    group: group.name.clone(),
+                    pattern: pattern.to_string(),
+                    message: message.to_string(),
+                });
+            }
+        }
+        self.validate_profiles()?;
+        for (hotkey, action) in self.bindings() {
+            self.validate_action(hotkey, &action)?;
+        }
+        Ok(())
+    }
+
+    fn validate_profiles(&self) -> Result<(), ConfigLoadError> {
+        let known = |id| self.profiles().iter().any(|p| p.id() == id);
+        if let Some(id) = self.active_profile_id()
+            && !known(id)
+        {
+            return Err(ConfigLoadError::UnknownProfile { id });
+        }
+        for profile in self.profiles() {
+            if let Some(id) = profile.groups.iter().find(|id| self.group(**id).is_err()) {
+                return Err(ConfigLoadError::UnknownGroup { id: *id });
+            }
+        }
+        for (_, action) in self.bindings() {
+            if let Action::SwitchProfile {
+                profile_id: Some(id),
+            } = action
+                && !known(id)
+            {
+                return Err(ConfigLoadError::UnknownProfile { id });
+            }
+        }
         Ok(())
     }
+
+    /// Sequences can't be empty or nested, nor contain actions the UI has to handle
+    fn validate_action(&self, hotkey: Hotkey, action: &Action) -> Result<(), ConfigLoadError> {
+        let (in_sequence, steps) = match action {
+            Action::Sequence { steps, .. } if steps.is_empty() => {
+                return Err(ConfigLoadError::EmptySequence { hotkey });
+            }
+            Action::Sequence { steps, .. } => (true, steps.iter().map(|s| &s.action).collect()),
+            action => (false, vec![action]),
+        };
+        for step in steps {
+            match step {
+                step if let Some(id) = step.group_id()
+                    && self.group(id).is_err() =>
+                {
+                    return Err(ConfigLoadError::UnknownGroup { id });
+                }
+                Action::Sequence { .. } => return Err(ConfigLoadError::NestedSequence { hotkey }),
+                step if in_sequence && step.targets_group_ctrl() => {
+                    return Err(ConfigLoadError::UnsupportedStep {
+                        hotkey,
+                        step: step.describe(self),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn quarantine(content: &str) {
+        if let Err(error) = std::fs::write(Self::quarantine_path(), content) {
+            warn!("could not quarantine broken config: {error}");
+        }
+    }
+}
+
+/// Writes to a temporary file first, so a crash can't leave a truncated file behind
+fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    let written =
+        write_synced(&temp_path, content).and_then(|()| std::fs::rename(&temp_path, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    written?;
+    sync_parent(path)
+}
+
+fn write_synced(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()
+}
+
+/// Persists the rename, which is recorded in the directory
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(dir) => File::open(dir)?.sync_all(),
+        None => Ok(()),
+    }
+}
+
+/// Windows can't open directories as files, and flushes renames with their metadata
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::SequenceStep;
+
+    #[test]
+    fn parse_valid() {
+        let content = "version: 1\ngroups:\n- name: Browsers\n  hotkey: Shift+F\n  target: null\n  apps: []\n";
+
+        let config = Config::parse(content).unwrap();
+
+        assert_eq!(config.groups()[0].name, "Browsers");
+    }
+
+    #[test]
+    fn parse_syntax_error() {
+        let content = "version: 1\ngroups:\n- name: Browsers\n  apps: [Safari\n";
+
+        let error = Config::parse(content).unwrap_err();
+
+        assert!(matches!(
+            error,
+            ConfigLoadError::Syntax {
+                line: 5,
+                column: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_unknown_modifier() {
+        let content = "version: 1\ngroups:\n- name: Browsers\n  hotkey: Hyperr+F\n  target: null\n  apps: []\n";
+
+        let error = Config::parse(content).unwrap_err();
+
+        assert!(matches!(
+            error,
+            ConfigLoadError::UnknownModifier { ref modifier, line: 4, column: 11 }
+                if modifier == "Hyperr"
+        ));
+    }
+
+    #[test]
+    fn parse_unknown_key() {
+        let content = "version: 1\ngroups:\n- name: Browsers\n  hotkey: Shift+Foo\n  target: null\n  apps: []\n";
+
+        let error = Config::parse(content).unwrap_err();
+
+        assert!(matches!(error, ConfigLoadError::UnknownKey { ref key, .. } if key == "Foo"));
+    }
+
+    #[test]
+    fn parse_unknown_field() {
+        let content = "version: 1\ngroups:\n- name: Browsers\n  hotkee: Shift+F\n  target: null\n  apps: []\n";
+
+        let error = Config::parse(content).unwrap_err();
+
+        assert!(matches!(
+            error,
+            ConfigLoadError::UnknownField { ref field, line: 4, .. } if field == "hotkee"
+        ));
+    }
+
+    #[test]
+    fn parse_duplicate_group_name() {
+        let group = "- name: Browsers\n  hotkey: null\n  target: null\n  apps: []\n";
+        let content = format!("version: 1\ngroups:\n{group}{group}");
+
+        let error = Config::parse(&content).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "There is more than one group named 'Browsers'"
+        );
+    }
+
+    #[test]
+    fn parse_keeps_group_ids() {
+        let mut config = Config::default();
+        let group_id = config.add_group("Browsers".to_string());
+        let content = serde_yaml::to_string(&config).unwrap();
+
+        let config = Config::parse(&content).unwrap();
+
+        assert_eq!(config.groups()[0].id(), group_id);
+    }
+
+    #[test]
+    fn version_of_saved_config() {
+        let content = serde_yaml::to_string(&Config::default()).unwrap();
+
+        assert_eq!(Config::version(&content).unwrap(), CURRENT_VERSION);
+        assert_eq!(Config::version("groups: []\n").unwrap(), 0);
+    }
+
+    #[test]
+    fn parse_duplicate_group_id() {
+        let id = "id: 67e55044-10b1-426f-9247-bb680e5fe0c8\n  hotkey: null\n  target: null\n  apps: []\n";
+        let content =
+            format!("version: 1\ngroups:\n- name: Browsers\n  {id}- name: Editors\n  {id}");
+
+        let error = Config::parse(&content).unwrap_err();
+
+        assert!(matches!(error, ConfigLoadError::DuplicateGroupId { .. }));
+    }
+
+    #[test]
+    fn parse_bindings() {
+        let content = format!(
+            "version: {CURRENT_VERSION}\ngroups: []\nbindings:\n- hotkey: Super+T\n  action:\n    type: run_command\n    argv: [open, -a, Terminal]\n- hotkey: Super+R\n  action:\n    type: reload_config\n"
+        );
+
+        let config = Config::parse(&content).unwrap();
+
+        let actions: Vec<_> = config.bindings().into_iter().map(|(_, a)| a).collect();
+        assert!(
+            matches!(&actions[0], Action::RunCommand { argv, cwd: None, .. } if argv.len() == 3)
+        );
+        assert_eq!(actions[1], Action::ReloadConfig);
+        let saved = serde_yaml::to_string(&config).unwrap();
+        assert_eq!(Config::parse(&saved).unwrap().bindings(), config.bindings());
+    }
+
+    #[test]
+    fn parse_launch_app_of_other_platform() {
+        let other = if cfg!(target_os = "macos") {
+            "linux"
+        } else {
+            "mac"
+        };
+        let content = format!(
+            "version: {CURRENT_VERSION}\ngroups: []\nbindings:\n- hotkey: Super+T\n  action:\n    type: launch_app\n    app:\n      {other}: x.app\n"
+        );
+
+        let config = Config::parse(&content).unwrap();
+
+        assert_eq!(serde_yaml::to_string(&config).unwrap(), content);
+    }
+
+    #[test]
+    fn parse_binding_to_group_action() {
+        let content = format!(
+            "version: {CURRENT_VERSION}\ngroups: []\nbindings:\n- hotkey: Super+T\n  action:\n    type: cycle_group\n"
+        );
+
+        let error = Config::parse(&content).unwrap_err();
+
+        assert!(matches!(error, ConfigLoadError::Syntax { .. }));
+    }
+
+    #[test]
+    fn parse_binding_to_unknown_group() {
+        let content = format!(
+            "version: {CURRENT_VERSION}\ngroups: []\nbindings:\n- hotkey: Super+T\n  action:\n    type: open_group\n    group_id: 67e55044-10b1-426f-9247-bb680e5fe0c8\n"
+        );
+
+        let error = Config::parse(&content).unwrap_err();
+
+        assert!(matches!(error, ConfigLoadError::UnknownGroup { .. }));
+    }
+
+    #[test]
+    fn parse_invalid_rule() {
+        let content = format!(
+            "version: {CURRENT_VERSION}\ngroups:\n- name: IDEs\n  hotkey: null\n  target: null\n  apps:\n  - regex: '(unclosed'\n"
+        );
+
+        let error = Config::parse(&content).unwrap_err();
+
+        assert!(matches!(error, ConfigLoadError::InvalidRule { .. }));
+    }
+
+    #[test]
+    fn parse_unknown_active_profile() {
+        let content = format!(
+            "version: {CURRENT_VERSION}\ngroups: []\nprofiles:\n- name: Work\n  groups: []\nactive_profile: 67e55044-10b1-426f-9247-bb680e5fe0c8\n"
+        );
+
+        let error = Config::parse(&content).unwrap_err();
+
+        assert!(matches!(error, ConfigLoadError::UnknownProfile { .. }));
+    }
+
+    fn sequence(steps: &str) -> String {
+        format!(
+            "version: {CURRENT_VERSION}\ngroups: []\nbindings:\n- hotkey: Super+T\n  action:\n    type: sequence\n    steps:\n{steps}"
+        )
+    }
+
+    #[test]
+    fn parse_sequence() {
+        let content = sequence(
+            "    - action:\n        type: open_url\n        url: https://example.com\n    - delay_ms: 500\n      action:\n        type: focus_previous_app\n",
+        );
+
+        let config = Config::parse(&content).unwrap();
+
+        let Action::Sequence {
+            steps,
+            stop_on_error,
+        } = &config.bindings()[0].1
+        else {
+            panic!("expected a sequence");
+        };
+        assert_eq!(steps[1].delay_ms, Some(500));
+        assert!(!stop_on_error);
+    }
+
+    #[test]
+    fn parse_empty_sequence() {
+        let content = sequence("      []\n");
+
+        let error = Config::parse(&content).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "The sequence bound to Super+T has no steps"
+        );
+    }
+
+    #[test]
+    fn parse_nested_sequence() {
+        let content = sequence("    - action:\n        type: sequence\n        steps: []\n");
+
+        let error = Config::parse(&content).unwrap_err();
+
+        assert!(matches!(error, ConfigLoadError::NestedSequence { .. }));
+    }
+
+    #[test]
+    fn parse_sequence_reloading_config() {
+        let content = sequence("    - action:\n        type: reload_config\n");
+
+        let error = Config::parse(&content).unwrap_err();
+
+        assert!(
+            matches!(error, ConfigLoadError::UnsupportedStep { ref step, .. } if step == "reload the config")
+        );
+    }
+
+    #[test]
+    fn write_atomically_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, "old").unwrap();
+
+        write_atomically(&path, "new").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn write_atomically_removes_temp_file_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("other.yaml"), "").unwrap(); // can't be replaced by a file
+
+        let result = write_atomically(&path, "new");
+
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn read_file_reports_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, format!("version: {CURRENT_VERSION}\ngroups: [\n")).unwrap();
+
+        let error = Config::read_file(&path).unwrap_err();
+
+        assert!(matches!(error, ConfigLoadError::Syntax { .. }));
+        assert!(Config::read_file(&dir.path().join("missing.yaml")).is_err());
+    }
+
+    #[test]
+    fn remove_group_with_bindings() {
+        let id = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        let step = |action: &str| format!("    - action:\n        type: {action}\n");
+        let content = format!(
+            "version: {CURRENT_VERSION}\ngroups:\n- name: Browsers\n  id: {id}\n  hotkey: null\n  target: null\n  apps: []\nbindings:\n- hotkey: Super+T\n  action:\n    type: open_group\n    group_id: {id}\n- hotkey: Super+S\n  action:\n    type: sequence\n    steps:\n{}{}- hotkey: Super+Q\n  action:\n    type: sequence\n    steps:\n{}",
+            step(&format!("launch_all_apps\n        group_id: {id}")),
+            step("focus_previous_app"),
+            step(&format!("quit_all_apps\n        group_id: {id}")),
+        );
+        let mut config = Config::parse(&content).unwrap();
+
+        config.remove_group(Uuid::parse_str(id).unwrap());
+        let saved = serde_yaml::to_string(&config).unwrap();
+
+        let (loaded, _) = Config::from_content(&saved).unwrap();
+        let steps = vec![SequenceStep {
+            delay_ms: None,
+            action: Action::FocusPreviousApp,
+        }];
+        let sequence = Action::Sequence {
+            steps,
+            stop_on_error: false,
+        };
+        assert_eq!(
+            loaded.bindings(),
+            vec![(Hotkey::try_from("Super+S".to_string()).unwrap(), sequence)]
+        );
+    }
 }
@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime};
+
+const PREFIX: &str = "config-";
+const EXTENSION: &str = ".yaml";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S%.3f"; // no colons for Windows
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub created: NaiveDateTime,
+}
+
+impl Backup {
+    fn from_path(path: PathBuf) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        let timestamp = name.strip_prefix(PREFIX)?.strip_suffix(EXTENSION)?;
+        let created = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+        Some(Self { path, created })
+    }
+}
+
+/// Stores `content` as a new backup and drops all but the `keep` newest ones
+pub(super) fn create(dir: &Path, content: &str, keep: usize) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let timestamp = Local::now().naive_local().format(TIMESTAMP_FORMAT);
+    std::fs::write(dir.join(format!("{PREFIX}{timestamp}{EXTENSION}")), content)?;
+    for backup in list(dir)?.into_iter().skip(keep) {
+        std::fs::remove_file(backup.path)?;
+    }
+    Ok(())
+}
+
+/// Newest first, ignoring unrelated files
+pub(super) fn list(dir: &Path) -> std::io::Result<Vec<Backup>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut backups: Vec<Backup> = entries
+        .filter_map(|entry| Backup::from_path(entry.ok()?.path()))
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created));
+    Ok(backups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_keeps_newest() {
+        // Arrange
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "unrelated").unwrap();
+
+        // Act
+        for i in 0..4 {
+            create(dir.path(), &format!("groups: {i}"), 3).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        // Assert
+        let backups = list(dir.path()).unwrap();
+        let contents: Vec<String> = backups
+            .iter()
+            .map(|backup| std::fs::read_to_string(&backup.path).unwrap())
+            .collect();
+        assert_eq!(contents, vec!["groups: 3", "groups: 2", "groups: 1"]);
+        assert!(dir.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn list_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let backups = list(&dir.path().join("backups")).unwrap();
+
+        assert!(backups.is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::CURRENT_VERSION;
+use crate::models::Hotkey;
+
+#[derive(Error, Debug)]
+pub enum ConfigLoadError {
+    #[error("Could not read config file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid config: {message}")] // message includes the location
+    Syntax {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+
+    #[error("Unknown setting '{field}' at line {line}, column {column}")]
+    UnknownField {
+        field: String,
+        line: usize,
+        column: usize,
+    },
+
+    #[error("Unknown key '{key}' at line {line}, column {column}")]
+    UnknownKey {
+        key: String,
+        line: usize,
+        column: usize,
+    },
+
+    #[error("Unknown modifier '{modifier}' at line {line}, column {column}")]
+    UnknownModifier {
+        modifier: String,
+        line: usize,
+        column: usize,
+    },
+
+    #[error("There is more than one group named '{name}'")]
+    DuplicateGroupName { name: String },
+
+    #[error("There is more than one group with id {id}")]
+    DuplicateGroupId { id: Uuid },
+
+    #[error("The rule '{pattern}' of group '{group}' is invalid: {message}")]
+    InvalidRule {
+        group: String,
+        pattern: String,
+        message: String,
+    },
+
+    #[error("There is no group with id {id}")]
+    UnknownGroup { id: Uuid },
+
+    #[error("There is no profile with id {id}")]
+    UnknownProfile { id: Uuid },
+
+    #[error("The sequence bound to {hotkey} has no steps")]
+    EmptySequence { hotkey: Hotkey },
+
+    #[error("The sequence bound to {hotkey} contains another sequence")]
+    NestedSequence { hotkey: Hotkey },
+
+    #[error("The sequence bound to {hotkey} can't {step}")]
+    UnsupportedStep { hotkey: Hotkey, step: String },
+
+    #[error(
+        "The config was written by a newer version of GroupCtrl (format version {version}, \
+         this build supports up to {CURRENT_VERSION}), please update"
+    )]
+    TooNew { version: u64 },
+
+    #[error("The format version '{version}' is not a whole number")]
+    InvalidVersion { version: String },
+}
+
+impl ConfigLoadError {
+    /// Whether the broken file was copied to the quarantine path
+    pub fn is_quarantined(&self) -> bool {
+        !matches!(self, Self::Io(_) | Self::TooNew { .. })
+    }
+
+    pub(super) fn from_yaml(error: serde_yaml::Error, content: &str) -> Self {
+        let location = error.location();
+        let (line, column) = location
+            .as_ref()
+            .map(|location| (location.line(), location.column()))
+            .unwrap_or_default();
+        // serde_yaml reports errors in values at the start of the enclosing
+        // mapping, so we look for the offending text from there
+        let index = location
+            .map(|location| location.index())
+            .unwrap_or_default();
+        let locate = |token: &str| locate(content, index, token).unwrap_or((line, column));
+        // serde only passes on our HotkeyParseError as text, so we recover it from there
+        let message = error.to_string();
+        if let Some(modifier) = quoted(&message, "unknown modifier '", '\'') {
+            let (line, column) = locate(&modifier);
+            Self::UnknownModifier {
+                modifier,
+                line,
+                column,
+            }
+        } else if let Some(key) = quoted(&message, "unknown key '", '\'') {
+            let (line, column) = locate(&key);
+            Self::UnknownKey { key, line, column }
+        } else if let Some(field) = quoted(&message, "unknown field `", '`') {
+            let (line, column) = locate(&field);
+            Self::UnknownField {
+                field,
+                line,
+                column,
+            }
+        } else {
+            Self::Syntax {
+                message,
+                line,
+                column,
+            }
+        }
+    }
+}
+
+fn quoted(message: &str, prefix: &str, end: char) -> Option<String> {
+    let start = message.find(prefix)? + prefix.len();
+    let len = message[start..].find(end)?;
+    Some(message[start..start + len].to_string())
+}
+
+/// One-based line and column of the first occurrence of `token` from `index` on
+fn locate(content: &str, index: usize, token: &str) -> Option<(usize, usize)> {
+    let pos = index + content.get(index..)?.find(token)?;
+    let before = &content[..pos];
+    let line = before.matches('\n').count() + 1;
+    let column = pos - before.rfind('\n').map_or(0, |newline| newline + 1) + 1;
+    Some((line, column))
+}
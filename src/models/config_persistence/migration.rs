@@ -0,0 +1,242 @@
+use serde_yaml::{Mapping, Value};
+use uuid::Uuid;
+
+use super::ConfigLoadError;
+use crate::models::Hotkey;
+
+/// Format version written by this build
+pub const CURRENT_VERSION: u64 = 12;
+
+/// Upgrades a document by one version, where index `n` migrates from version `n`.
+/// Versions that only add optional settings change nothing, but keep older
+/// builds from reading files whose settings they would reject or drop.
+const MIGRATIONS: [fn(&mut Value); CURRENT_VERSION as usize] = [
+    assign_group_ids,
+    canonical_modifiers,
+    unchanged, // version 3 adds cycle orders
+    disable_reverse_hotkeys,
+    unchanged, // version 5 adds repeat policies
+    unchanged, // version 6 adds standalone bindings
+    unchanged, // version 7 adds sequences
+    unchanged, // version 8 adds chords
+    unchanged, // version 9 adds gestures
+    unchanged, // version 10 adds profiles
+    unchanged, // version 11 adds launch-all and quit-all actions
+    unchanged, // version 12 adds app rules
+];
+
+/// Files without a `version` key predate versioning
+pub(super) fn version(document: &Value) -> Result<u64, ConfigLoadError> {
+    let Some(version) = document.get("version") else {
+        return Ok(0);
+    };
+    version
+        .as_u64()
+        .ok_or_else(|| ConfigLoadError::InvalidVersion {
+            version: serde_yaml::to_string(version)
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        })
+}
+
+pub(super) fn migrate(document: &mut Value, from: u64) {
+    for migration in &MIGRATIONS[from as usize..] {
+        migration(document);
+    }
+    if let Some(mapping) = document.as_mapping_mut() {
+        mapping.insert("version".into(), CURRENT_VERSION.into());
+    }
+}
+
+/// Version 1 persists group ids, which used to be regenerated on every launch
+fn assign_group_ids(document: &mut Value) {
+    for group in groups_mut(document) {
+        if !group.contains_key("id") {
+            group.insert("id".into(), Uuid::new_v4().to_string().into());
+        }
+    }
+}
+
+/// Version 2 writes platform-neutral modifier names like `Super` for `Cmd` or `Win`,
+/// which older builds can't read. Until then only groups had hotkeys. Hotkeys that
+/// don't parse are left alone, so loading reports them with their location.
+fn canonical_modifiers(document: &mut Value) {
+    for group in groups_mut(document) {
+        let Some(hotkey) = group.get_mut("hotkey") else {
+            continue;
+        };
+        if let Some(text) = hotkey.as_str()
+            && let Ok(parsed) = Hotkey::try_from(text.to_string())
+        {
+            *hotkey = String::from(parsed).into();
+        }
+    }
+}
+
+/// Version 4 adds reverse hotkeys. Existing groups get none, as Shift plus their
+/// hotkey may already be used elsewhere, while new groups derive one.
+fn disable_reverse_hotkeys(document: &mut Value) {
+    for group in groups_mut(document) {
+        if !group.contains_key("reverse_hotkey") {
+            group.insert("reverse_hotkey".into(), Value::Null);
+        }
+    }
+}
+
+fn unchanged(_document: &mut Value) {}
+
+fn groups_mut(document: &mut Value) -> impl Iterator<Item = &mut Mapping> {
+    document
+        .get_mut("groups")
+        .and_then(|groups| groups.as_sequence_mut())
+        .into_iter()
+        .flatten()
+        .filter_map(|group| group.as_mapping_mut())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_unversioned() {
+        // Arrange
+        let mut document: Value = serde_yaml::from_str("groups:\n- name: Browsers\n").unwrap();
+
+        // Act
+        migrate(&mut document, 0);
+
+        // Assert
+        assert_eq!(version(&document).unwrap(), CURRENT_VERSION);
+        assert!(document["groups"][0]["id"].as_str().is_some());
+    }
+
+    #[test]
+    fn migrate_keeps_existing_ids() {
+        // Arrange
+        let id = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        let content = format!("groups:\n- name: Browsers\n  id: {id}\n");
+        let mut document: Value = serde_yaml::from_str(&content).unwrap();
+
+        // Act
+        migrate(&mut document, 0);
+
+        // Assert
+        assert_eq!(document["groups"][0]["id"].as_str(), Some(id));
+    }
+
+    #[test]
+    fn reject_invalid_version() {
+        // Arrange
+        let document: Value = serde_yaml::from_str("version: -1\ngroups: []\n").unwrap();
+
+        // Act
+        let error = version(&document).unwrap_err();
+
+        // Assert
+        assert!(
+            matches!(error, ConfigLoadError::InvalidVersion { ref version } if version == "-1")
+        );
+    }
+
+    #[test]
+    fn migrate_platform_modifiers() {
+        // Arrange
+        let content = "version: 1\ngroups:\n- name: Browsers\n  hotkey: Cmd+Opt+B\n- name: IDEs\n  hotkey: Cmd+Foo\n";
+        let mut document: Value = serde_yaml::from_str(content).unwrap();
+
+        // Act
+        migrate(&mut document, 1);
+
+        // Assert
+        assert_eq!(
+            document["groups"][0]["hotkey"].as_str(),
+            Some("Super+Alt+B")
+        );
+        assert_eq!(document["groups"][1]["hotkey"].as_str(), Some("Cmd+Foo"));
+    }
+
+    /// Runs only the migration from the version in `content`, expecting no changes
+    fn assert_migrates_unchanged(content: &str) {
+        let mut document: Value = serde_yaml::from_str(content).unwrap();
+        let expected = document.clone();
+        MIGRATIONS[version(&document).unwrap() as usize](&mut document);
+        assert_eq!(document, expected);
+    }
+
+    #[test]
+    fn migrate_to_cycle_orders() {
+        // Groups keep cycling in config order
+        assert_migrates_unchanged("version: 2\ngroups:\n- name: Browsers\n  hotkey: Super+B\n");
+    }
+
+    #[test]
+    fn migrate_without_reverse_hotkeys() {
+        // Arrange
+        let content = "version: 3\ngroups:\n- name: Browsers\n  hotkey: Super+B\n";
+        let mut document: Value = serde_yaml::from_str(content).unwrap();
+
+        // Act
+        migrate(&mut document, 3);
+
+        // Assert
+        assert!(document["groups"][0]["reverse_hotkey"].is_null());
+    }
+
+    #[test]
+    fn migrate_to_repeat_policies() {
+        // Groups keep cycling on repeated presses
+        assert_migrates_unchanged(
+            "version: 4\ngroups:\n- name: Browsers\n  hotkey: Super+B\n  reverse_hotkey: null\n",
+        );
+    }
+
+    #[test]
+    fn migrate_to_bindings() {
+        // Groups keep the only hotkeys
+        assert_migrates_unchanged("version: 5\ngroups:\n- name: Browsers\n  hotkey: Super+B\n");
+    }
+
+    #[test]
+    fn migrate_to_sequences() {
+        // Groups keep single actions
+        assert_migrates_unchanged(
+            "version: 6\ngroups: []\nbindings:\n- hotkey: Super+R\n  action:\n    type: reload_config\n",
+        );
+    }
+
+    #[test]
+    fn migrate_to_chords() {
+        // Groups keep single hotkeys
+        assert_migrates_unchanged("version: 7\ngroups:\n- name: Browsers\n  hotkey: Super+B\n");
+    }
+
+    #[test]
+    fn migrate_to_gestures() {
+        // Groups keep without gestures
+        assert_migrates_unchanged("version: 8\ngroups:\n- name: Browsers\n  hotkey: Super+B\n");
+    }
+
+    #[test]
+    fn migrate_to_profiles() {
+        // Groups keep enabled, as without an active profile
+        assert_migrates_unchanged("version: 9\ngroups:\n- name: Browsers\n  hotkey: Super+B\n");
+    }
+
+    #[test]
+    fn migrate_to_launch_and_quit_all() {
+        // Groups keep as they were
+        assert_migrates_unchanged(
+            "version: 10\ngroups: []\nbindings:\n- hotkey: Super+R\n  action:\n    type: reload_config\n",
+        );
+    }
+
+    #[test]
+    fn migrate_to_app_rules() {
+        // Groups keep listing app ids
+        assert_migrates_unchanged(
+            "version: 11\ngroups:\n- name: Browsers\n  hotkey: Super+B\n  apps: [com.apple.Safari]\n",
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// How a hotkey is pressed, so that one hotkey can trigger several actions
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Gesture {
+    #[default]
+    Press,
+    DoublePress,
+    Hold,
+}
+
+impl Gesture {
+    pub fn is_press(&self) -> bool {
+        *self == Self::Press
+    }
+}
+
+/// Tells gestures apart, in milliseconds
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GestureTiming {
+    pub double_press_ms: u64, // max time between releasing and pressing again
+    pub hold_ms: u64,         // min time between pressing and releasing
+}
+
+impl Default for GestureTiming {
+    fn default() -> Self {
+        Self {
+            double_press_ms: 300,
+            hold_ms: 500,
+        }
+    }
+}
+
+impl GestureTiming {
+    pub(super) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn double_press(&self) -> Duration {
+        Duration::from_millis(self.double_press_ms)
+    }
+
+    pub fn hold(&self) -> Duration {
+        Duration::from_millis(self.hold_ms)
+    }
+}
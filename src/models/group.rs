@@ -3,18 +3,98 @@ use std::fmt::{Display, Formatter};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::models::Hotkey;
+use crate::models::app_entry::AppEntry;
 use crate::models::traits::Identifiable;
+use crate::models::{AppMatcher, Hotkey};
 use crate::os::App;
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Group {
-    #[serde(skip, default = "Uuid::new_v4")]
+    #[serde(default = "Uuid::new_v4")] // legacy configs lack ids
     id: Uuid,
     pub name: String,
     pub hotkey: Option<Hotkey>,
-    pub target: Option<App>,
-    apps: Vec<App>,
+    target: Option<AppEntry>,
+    apps: Vec<AppEntry>, // kept in full, even if unknown on this platform
+    #[serde(default, skip_serializing_if = "CycleOrder::is_default")]
+    pub cycle: CycleOrder,
+    #[serde(default, skip_serializing_if = "ReverseHotkey::is_derived")]
+    pub reverse_hotkey: ReverseHotkey,
+    #[serde(default, skip_serializing_if = "RepeatPolicy::is_default")]
+    pub on_repeat: RepeatPolicy,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub gestures: bool, // double press opens the target, hold launches all apps
+}
+
+/// Secondary hotkey for cycling backwards, written as a hotkey or `null` to disable it
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(from = "Option<Hotkey>", into = "Option<Hotkey>")]
+pub enum ReverseHotkey {
+    #[default]
+    Derived, // the group hotkey plus Shift
+    Custom(Hotkey),
+    Disabled,
+}
+
+impl ReverseHotkey {
+    fn is_derived(&self) -> bool {
+        *self == Self::Derived
+    }
+
+    pub fn resolve(self, hotkey: Option<Hotkey>) -> Option<Hotkey> {
+        match self {
+            Self::Derived => hotkey?.with_shift(),
+            Self::Custom(hotkey) => Some(hotkey),
+            Self::Disabled => None,
+        }
+    }
+}
+
+impl From<Option<Hotkey>> for ReverseHotkey {
+    fn from(hotkey: Option<Hotkey>) -> Self {
+        hotkey.map_or(Self::Disabled, Self::Custom)
+    }
+}
+
+impl From<ReverseHotkey> for Option<Hotkey> {
+    fn from(reverse_hotkey: ReverseHotkey) -> Self {
+        match reverse_hotkey {
+            ReverseHotkey::Custom(hotkey) => Some(hotkey),
+            ReverseHotkey::Derived | ReverseHotkey::Disabled => None,
+        }
+    }
+}
+
+/// How repeated presses move through the apps of a group
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CycleOrder {
+    #[default]
+    Order, // as listed in the config
+    Recent, // most recently used first, like Cmd-Tab
+}
+
+impl CycleOrder {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What pressing the hotkey does once the last app of a group is reached
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepeatPolicy {
+    #[default]
+    Cycle, // start over with the first app
+    ToggleBack, // return to the app used before entering the group
+    Hide,       // hide all apps of the group
+}
+
+impl RepeatPolicy {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 impl Identifiable<Uuid> for Group {
@@ -31,25 +111,75 @@ impl Group {
             hotkey: None,
             target: None,
             apps: Vec::new(),
+            cycle: CycleOrder::default(),
+            reverse_hotkey: ReverseHotkey::default(),
+            on_repeat: RepeatPolicy::default(),
+            gestures: false,
         }
     }
 
-    pub fn apps(&self) -> &Vec<App> {
-        &self.apps
+    /// Apps known on the current platform
+    pub fn apps(&self) -> Vec<App> {
+        self.apps
+            .iter()
+            .filter_map(AppEntry::app)
+            .cloned()
+            .collect()
+    }
+
+    /// Apps of the group followed by the `candidates` its rules match, in
+    /// alphabetical order so cycling through them is stable
+    pub fn resolve_apps(&self, candidates: &[App]) -> Vec<App> {
+        let mut apps = self.apps();
+        let matchers = self.matchers();
+        let mut matched: Vec<App> = candidates
+            .iter()
+            .filter(|app| !apps.contains(app))
+            .filter(|app| matchers.iter().any(|m| m.matches(app)))
+            .cloned()
+            .collect();
+        matched.sort_by_key(|app| app.id());
+        matched.dedup();
+        apps.append(&mut matched);
+        apps
+    }
+
+    pub fn matchers(&self) -> Vec<&AppMatcher> {
+        self.apps.iter().filter_map(AppEntry::matcher).collect()
+    }
+
+    pub(super) fn matcher_errors(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.apps.iter().filter_map(AppEntry::matcher_error)
+    }
+
+    pub fn target(&self) -> Option<App> {
+        self.target.as_ref()?.app().cloned()
+    }
+
+    /// Reuses the entry of the app, so its ids for other platforms are kept
+    pub(super) fn set_target(&mut self, app: Option<App>) {
+        self.target = app.map(|app| {
+            self.apps
+                .iter()
+                .find(|entry| entry.app().is_some_and(|a| a.id() == app.id()))
+                .cloned()
+                .unwrap_or_else(|| app.into())
+        });
     }
 
     pub(super) fn add_app(&mut self, app: App) {
-        if self.apps.iter().any(|a| a.id() == app.id()) {
+        if self.apps().iter().any(|a| a.id() == app.id()) {
             return;
         }
-        self.apps.push(app);
+        self.apps.push(app.into());
     }
 
     pub(super) fn remove_app(&mut self, app_id: String) {
-        if self.target.as_ref().map(|a| a.id()).as_deref() == Some(&app_id) {
+        if self.target().map(|a| a.id()).as_deref() == Some(&app_id) {
             self.target = None;
         }
-        self.apps.retain(|a| a.id() != app_id)
+        self.apps
+            .retain(|entry| entry.app().is_none_or(|a| a.id() != app_id))
     }
 }
 
@@ -58,3 +188,34 @@ impl Display for Group {
         write!(f, "{}", self.name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURRENT: &str = if cfg!(target_os = "macos") {
+        "mac"
+    } else if cfg!(target_os = "windows") {
+        "windows"
+    } else {
+        "linux"
+    };
+
+    const OTHER: &str = if cfg!(target_os = "macos") {
+        "linux"
+    } else {
+        "mac"
+    };
+
+    #[test]
+    fn set_target_keeps_other_platforms() {
+        let content = format!(
+            "name: Browsers\nhotkey: null\ntarget: null\napps:\n- {OTHER}: x.app\n  {CURRENT}: com.example.app\n"
+        );
+        let mut group: Group = serde_yaml::from_str(&content).unwrap();
+
+        group.set_target(Some(App::from("com.example.app".to_string())));
+
+        assert_eq!(group.target, Some(group.apps[0].clone()));
+    }
+}
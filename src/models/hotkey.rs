@@ -3,25 +3,88 @@ use std::fmt::{Debug, Display, Formatter};
 use global_hotkey::hotkey::{Code, HotKey as GlobalHotkey, Modifiers};
 use serde::{Deserialize, Serialize};
 
+use crate::models::Gesture;
 use crate::models::hotkey_conversion::show_hotkey_parts;
 use crate::os::{Keyboard, System};
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(into = "String", from = "String")]
+#[serde(into = "String", try_from = "String")]
 pub struct Hotkey {
     pub(super) mods: Modifiers,
     pub(super) key: Code,
+    pub(super) follow_up: Option<(Modifiers, Code)>, // makes this a chord
+    pub(super) gesture: Gesture,                     // not part of the string form, see `Binding`
 }
 
 impl Hotkey {
     pub fn new(mods: Modifiers, key: Code) -> Hotkey {
-        Self { mods, key }
+        Self {
+            mods,
+            key,
+            follow_up: None,
+            gesture: Gesture::Press,
+        }
     }
 
+    /// The same keystrokes, triggered by `gesture` instead of a single press
+    pub fn with_gesture(self, gesture: Gesture) -> Hotkey {
+        Self { gesture, ..self }
+    }
+
+    pub fn gesture(self) -> Gesture {
+        self.gesture
+    }
+
+    /// The keystrokes alone, which are registered with the OS
+    pub fn without_gesture(self) -> Hotkey {
+        self.with_gesture(Gesture::Press)
+    }
+
+    /// A chord, where `follow_up` has to be pressed shortly after this hotkey
+    pub fn then(self, follow_up: Hotkey) -> Hotkey {
+        let follow_up = Some((follow_up.mods, follow_up.key));
+        Self { follow_up, ..self }
+    }
+
+    pub fn is_chord(self) -> bool {
+        self.follow_up.is_some()
+    }
+
+    /// The first keystroke, which is the whole hotkey unless it's a chord
+    pub fn leader(self) -> Hotkey {
+        Self::new(self.mods, self.key)
+    }
+
+    pub fn follow_up(self) -> Option<Hotkey> {
+        let (mods, key) = self.follow_up?;
+        Some(Self::new(mods, key))
+    }
+
+    /// Whether one gets in the way of the other, like a hotkey and a chord it leads,
+    /// or a chord whose follow-up is already registered while the chord is pending
+    pub fn conflicts_with(self, other: Hotkey) -> bool {
+        self == other
+            || (self.is_chord() != other.is_chord() && self.leader() == other.leader())
+            || self.follow_up() == Some(other.leader())
+            || other.follow_up() == Some(self.leader())
+    }
+
+    /// `None` if Shift is already part of the hotkey, or of the follow-up of a chord
+    pub fn with_shift(self) -> Option<Hotkey> {
+        let shifted = match self.follow_up() {
+            Some(follow_up) => self.leader().then(follow_up.with_shift()?),
+            None => Self::new(self.mods | Modifiers::SHIFT, self.key),
+        };
+        (shifted != self).then_some(shifted)
+    }
+
+    /// The keystroke that completes the hotkey, i.e. the follow-up of a chord
     pub fn global_hotkey(self) -> GlobalHotkey {
-        GlobalHotkey::new(Some(self.mods), self.key)
+        let (mods, key) = self.follow_up.unwrap_or((self.mods, self.key));
+        GlobalHotkey::new(Some(mods), key)
     }
 
+    /// Parts of the first keystroke, see `leader`
     pub fn show_parts(&self) -> Vec<String> {
         show_hotkey_parts(self)
     }
@@ -29,12 +92,25 @@ impl Hotkey {
 
 impl Display for Hotkey {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.show_parts().join(System::show_parts_sep()))
+        let show = |hotkey: Hotkey| hotkey.show_parts().join(System::show_parts_sep());
+        match self.follow_up() {
+            Some(follow_up) => write!(f, "{}, {}", show(*self), show(follow_up))?,
+            None => write!(f, "{}", show(*self))?,
+        }
+        match self.gesture {
+            Gesture::Press => Ok(()),
+            Gesture::DoublePress => write!(f, " ×2"),
+            Gesture::Hold => write!(f, " (hold)"),
+        }
     }
 }
 
 impl Debug for Hotkey {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", String::from(*self)) // uses From impl, different from to_string
+        write!(f, "{}", String::from(*self))?; // uses From impl, different from to_string
+        match self.gesture {
+            Gesture::Press => Ok(()),
+            gesture => write!(f, " ({gesture:?})"),
+        }
     }
 }
@@ -1,11 +1,58 @@
 use global_hotkey::hotkey::{Code, Modifiers};
+use thiserror::Error;
 
 use super::Hotkey;
 use crate::os::{Keyboard, ModifierFormat, System};
 
 const SERDE_SEP: &str = "+";
+const SERDE_CHORD_SEP: &str = ", ";
 const KEY_PREFIXES: [&str; 4] = ["Key", "Digit", "Arrow", ""];
 
+/// Written on every platform, so one config works everywhere
+const SERDE_MODIFIER_FORMAT: ModifierFormat = [
+    (Modifiers::META, "Super"),
+    (Modifiers::CONTROL, "Ctrl"),
+    (Modifiers::ALT, "Alt"),
+    (Modifiers::SHIFT, "Shift"),
+];
+
+/// Shorthands for common combinations, written in place of their parts
+const SERDE_MODIFIER_SHORTHANDS: [(Modifiers, &str); 2] = [
+    (
+        Modifiers::META
+            .union(Modifiers::CONTROL)
+            .union(Modifiers::ALT)
+            .union(Modifiers::SHIFT),
+        "Hyper",
+    ),
+    (
+        Modifiers::CONTROL
+            .union(Modifiers::ALT)
+            .union(Modifiers::SHIFT),
+        "Meh",
+    ),
+];
+
+/// Platform spellings written by earlier versions, still accepted when reading
+const SERDE_MODIFIER_ALIASES: [(Modifiers, &str); 7] = [
+    (Modifiers::META, "Cmd"),
+    (Modifiers::META, "Command"),
+    (Modifiers::META, "Win"),
+    (Modifiers::META, "Meta"),
+    (Modifiers::CONTROL, "Control"),
+    (Modifiers::ALT, "Opt"),
+    (Modifiers::ALT, "Option"),
+];
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum HotkeyParseError {
+    #[error("unknown modifier '{0}'")]
+    UnknownModifier(String),
+
+    #[error("unknown key '{0}'")]
+    UnknownKey(String),
+}
+
 pub(super) fn show_hotkey_parts(hotkey: &Hotkey) -> Vec<String> {
     let mut parts = mods_to_string_vec(hotkey.mods, System::show_modifier_format());
     let key_show = System::show_key(hotkey.key).unwrap_or_else(|| show_key_common(hotkey.key));
@@ -15,22 +62,55 @@ pub(super) fn show_hotkey_parts(hotkey: &Hotkey) -> Vec<String> {
 
 impl From<Hotkey> for String {
     fn from(hotkey: Hotkey) -> Self {
-        let mut parts = mods_to_string_vec(hotkey.mods, System::serde_modifier_format());
-        parts.push(key_to_string(hotkey.key));
-        parts.join(SERDE_SEP)
+        match hotkey.follow_up() {
+            Some(follow_up) => {
+                let leader = keystroke_to_string(hotkey);
+                format!(
+                    "{leader}{SERDE_CHORD_SEP}{}",
+                    keystroke_to_string(follow_up)
+                )
+            }
+            None => keystroke_to_string(hotkey),
+        }
     }
 }
 
-impl From<String> for Hotkey {
-    fn from(s: String) -> Self {
-        let parts: Vec<&str> = s.split(SERDE_SEP).collect();
-        let (mod_parts, key_part) = parts.split_at(parts.len() - 1);
-        let mods = parse_mods(mod_parts, System::serde_modifier_format());
-        let key = parse_key(key_part[0]);
-        Hotkey::new(mods, key)
+impl TryFrom<String> for Hotkey {
+    type Error = HotkeyParseError;
+
+    /// Chords are written as two keystrokes separated by a comma, like `Hyper+G, W`
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match s.split_once(SERDE_CHORD_SEP.trim()) {
+            Some((leader, follow_up)) => {
+                Ok(parse_keystroke(leader)?.then(parse_keystroke(follow_up)?))
+            }
+            None => parse_keystroke(&s),
+        }
     }
 }
 
+fn keystroke_to_string(hotkey: Hotkey) -> String {
+    let mut parts = Vec::new();
+    let mut mods = hotkey.mods;
+    for (shorthand, text) in SERDE_MODIFIER_SHORTHANDS {
+        if mods.contains(shorthand) {
+            parts.push(text.to_string());
+            mods.remove(shorthand);
+        }
+    }
+    parts.extend(mods_to_string_vec(mods, SERDE_MODIFIER_FORMAT));
+    parts.push(key_to_string(hotkey.key));
+    parts.join(SERDE_SEP)
+}
+
+fn parse_keystroke(s: &str) -> Result<Hotkey, HotkeyParseError> {
+    let parts: Vec<&str> = s.trim().split(SERDE_SEP).collect();
+    let (mod_parts, key_part) = parts.split_at(parts.len() - 1);
+    let mods = parse_mods(mod_parts)?;
+    let key = parse_key(key_part[0])?;
+    Ok(Hotkey::new(mods, key))
+}
+
 fn mods_to_string_vec(mods: Modifiers, modifier_format: ModifierFormat) -> Vec<String> {
     modifier_format
         .iter()
@@ -65,24 +145,163 @@ fn show_key_common(key: Code) -> String {
     }
 }
 
-fn parse_part(part: &str, modifier_format: ModifierFormat) -> Modifiers {
-    modifier_format
+fn parse_part(part: &str) -> Result<Modifiers, HotkeyParseError> {
+    SERDE_MODIFIER_FORMAT
         .iter()
-        .find(|(_, text)| *text == part)
+        .chain(&SERDE_MODIFIER_SHORTHANDS)
+        .chain(&SERDE_MODIFIER_ALIASES)
+        .find(|(_, text)| text.eq_ignore_ascii_case(part))
         .map(|(m, _)| *m)
-        .unwrap_or_else(|| panic!("unknown modifier: {part}"))
+        .ok_or_else(|| HotkeyParseError::UnknownModifier(part.to_string()))
 }
 
-fn parse_mods(parts: &[&str], modifier_format: ModifierFormat) -> Modifiers {
+fn parse_mods(parts: &[&str]) -> Result<Modifiers, HotkeyParseError> {
     parts
         .iter()
-        .map(|part| parse_part(part, modifier_format))
-        .fold(Modifiers::empty(), |acc, m| acc | m)
+        .try_fold(Modifiers::empty(), |acc, part| Ok(acc | parse_part(part)?))
 }
 
-fn parse_key(string: &str) -> Code {
+fn parse_key(string: &str) -> Result<Code, HotkeyParseError> {
     KEY_PREFIXES
         .iter()
         .find_map(|prefix| format!("{prefix}{string}").parse::<Code>().ok())
-        .unwrap_or_else(|| panic!("unknown key: {string}"))
+        .ok_or_else(|| HotkeyParseError::UnknownKey(string.to_string()))
+}
+
+#[cfg(test)]
+mod all_codes;
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use proptest::sample::{select, subsequence};
+
+    use super::all_codes::ALL_CODES;
+    use super::*;
+
+    const MODIFIERS: [Modifiers; 4] = [
+        Modifiers::META,
+        Modifiers::CONTROL,
+        Modifiers::ALT,
+        Modifiers::SHIFT,
+    ];
+
+    fn modifiers() -> impl Strategy<Value = Modifiers> {
+        subsequence(MODIFIERS.to_vec(), 0..=MODIFIERS.len())
+            .prop_map(|mods| mods.into_iter().fold(Modifiers::empty(), |acc, m| acc | m))
+    }
+
+    /// Combination of `MODIFIERS` selected by the bits of `n`
+    fn modifier_subset(n: usize) -> Modifiers {
+        MODIFIERS
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| n & (1 << i) != 0)
+            .fold(Modifiers::empty(), |acc, (_, m)| acc | m)
+    }
+
+    fn parse(s: &str) -> Result<Hotkey, HotkeyParseError> {
+        Hotkey::try_from(s.to_string())
+    }
+
+    #[test]
+    fn round_trip_all_codes() {
+        for key in ALL_CODES {
+            for mods in (0..1 << MODIFIERS.len()).map(modifier_subset) {
+                let hotkey = Hotkey::new(mods, key);
+
+                let parsed = parse(&String::from(hotkey));
+
+                assert_eq!(parsed, Ok(hotkey), "{hotkey:?}");
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip_any_case(
+            mods in modifiers(),
+            key in select(ALL_CODES.to_vec()),
+            uppercase in any::<bool>(),
+        ) {
+            let hotkey = Hotkey::new(mods, key);
+            let canonical = String::from(hotkey);
+            let spelling = match canonical.rsplit_once(SERDE_SEP) {
+                Some((mod_parts, key_part)) if uppercase => {
+                    format!("{}{SERDE_SEP}{key_part}", mod_parts.to_uppercase())
+                }
+                Some((mod_parts, key_part)) => {
+                    format!("{}{SERDE_SEP}{key_part}", mod_parts.to_lowercase())
+                }
+                None => canonical,
+            };
+
+            let parsed = parse(&spelling);
+
+            prop_assert_eq!(parsed, Ok(hotkey));
+        }
+
+        #[test]
+        fn parse_aliases(
+            alias in select(SERDE_MODIFIER_ALIASES.to_vec()),
+            key in select(ALL_CODES.to_vec()),
+        ) {
+            let (mods, text) = alias;
+
+            let parsed = parse(&format!("{}+{}", text.to_lowercase(), key_to_string(key)));
+
+            prop_assert_eq!(parsed, Ok(Hotkey::new(mods, key)));
+        }
+    }
+
+    #[test]
+    fn serialize_canonical() {
+        let hotkey = Hotkey::new(Modifiers::META | Modifiers::ALT, Code::KeyK);
+
+        assert_eq!(String::from(hotkey), "Super+Alt+K");
+    }
+
+    #[test]
+    fn serialize_shorthands() {
+        let hyper = Hotkey::new(
+            MODIFIERS.into_iter().fold(Modifiers::empty(), |a, m| a | m),
+            Code::KeyG,
+        );
+        let meh = Hotkey::new(
+            Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT,
+            Code::Digit1,
+        );
+
+        assert_eq!(String::from(hyper), "Hyper+G");
+        assert_eq!(String::from(meh), "Meh+1");
+    }
+
+    #[test]
+    fn parse_platform_spellings() {
+        let expected = Hotkey::new(Modifiers::META | Modifiers::ALT, Code::KeyK);
+
+        assert_eq!(parse("Cmd+Opt+K"), Ok(expected));
+        assert_eq!(parse("Win+Alt+K"), Ok(expected));
+    }
+
+    #[test]
+    fn round_trip_chord() {
+        let leader = Hotkey::new(
+            MODIFIERS.into_iter().fold(Modifiers::empty(), |a, m| a | m),
+            Code::KeyG,
+        );
+        let chord = leader.then(Hotkey::new(Modifiers::empty(), Code::Comma));
+
+        assert_eq!(String::from(chord), "Hyper+G, Comma");
+        assert_eq!(parse("Hyper+G, Comma"), Ok(chord));
+        assert_eq!(parse("hyper+G,Comma"), Ok(chord));
+    }
+
+    #[test]
+    fn parse_unknown_modifier() {
+        assert_eq!(
+            parse("Hyperr+K"),
+            Err(HotkeyParseError::UnknownModifier("Hyperr".to_string()))
+        );
+    }
 }
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::traits::Identifiable;
+
+/// A named set of enabled groups, so groups of different profiles can share hotkeys
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default = "Uuid::new_v4")] // may be left out when written by hand
+    id: Uuid,
+    pub name: String,
+    pub groups: Vec<Uuid>,
+}
+
+impl Identifiable<Uuid> for Profile {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
@@ -4,15 +4,25 @@ pub use dirs::*;
 mod traits;
 pub use traits::*;
 
-#[cfg(target_os = "macos")]
+#[cfg(feature = "sim")]
+mod sim;
+#[cfg(feature = "sim")]
+pub use sim::*;
+
+#[cfg(all(target_os = "macos", not(feature = "sim")))]
 mod macos;
-#[cfg(target_os = "macos")]
+#[cfg(all(target_os = "macos", not(feature = "sim")))]
 pub use macos::*;
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "sim")))]
 mod windows;
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "sim")))]
 pub use windows::*;
 
-#[cfg(not(any(target_os = "macos", target_os = "windows")))]
-compile_error!("GroupCtrl only supports macOS and Windows");
+#[cfg(all(target_os = "linux", not(feature = "sim")))]
+mod linux;
+#[cfg(all(target_os = "linux", not(feature = "sim")))]
+pub use linux::*;
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+compile_error!("GroupCtrl only supports macOS, Windows and Linux");
@@ -12,11 +12,25 @@ pub fn icons_dir() -> PathBuf {
     data_local_dir().join("icons")
 }
 
-#[cfg(not(debug_assertions))]
 pub fn logs_dir() -> PathBuf {
     data_local_dir().join("logs")
 }
 
+/// Where a running instance listens for requests, see `ipc`
+pub fn socket_path() -> PathBuf {
+    data_local_dir().join("groupctrl.sock")
+}
+
+/// Where the settings window started by the daemon listens, see `Daemon::open_settings`
+pub fn settings_socket_path() -> PathBuf {
+    data_local_dir().join("groupctrl-settings.sock")
+}
+
+/// Held by the running instance, see `InstanceLock`
+pub fn lock_path() -> PathBuf {
+    data_local_dir().join("groupctrl.lock")
+}
+
 fn data_local_dir() -> PathBuf {
     dirs::data_local_dir()
         .expect("could not determine data local directory")
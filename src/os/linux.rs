@@ -0,0 +1,19 @@
+mod app;
+mod app_hideable;
+mod app_metadata;
+mod app_observer;
+mod app_openable;
+mod app_query;
+mod app_quittable;
+mod app_selection;
+mod config_dir;
+mod desktop_entry;
+mod icon_theme;
+mod key_file;
+mod keyboard;
+mod x11;
+mod xdg;
+
+pub use app::App;
+
+pub struct System;
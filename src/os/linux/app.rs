@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::app_metadata;
+use super::desktop_entry::DesktopEntry;
+use crate::models::Identifiable;
+use crate::os::AppMetadata;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub struct App {
+    pub(super) desktop_id: String,
+    pub(super) entry: Option<DesktopEntry>,
+    name: String,
+    icon_path: Option<PathBuf>,
+}
+
+impl App {
+    pub(super) fn new(
+        desktop_id: String,
+        entry: Option<DesktopEntry>,
+        name: String,
+        icon_path: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            desktop_id,
+            entry,
+            name,
+            icon_path,
+        }
+    }
+}
+
+impl Identifiable<String> for App {
+    fn id(&self) -> String {
+        self.desktop_id.clone()
+    }
+}
+
+impl AppMetadata for App {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn icon_path(&self) -> Option<&Path> {
+        self.icon_path.as_deref()
+    }
+}
+
+impl From<App> for String {
+    fn from(app: App) -> Self {
+        app.desktop_id
+    }
+}
+
+impl From<String> for App {
+    fn from(desktop_id: String) -> Self {
+        app_metadata::resolve(&desktop_id)
+    }
+}
+
+impl Display for App {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl Hash for App {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.desktop_id.hash(state);
+    }
+}
+
+impl PartialEq for App {
+    fn eq(&self, other: &Self) -> bool {
+        self.desktop_id == other.desktop_id
+    }
+}
+
+impl Eq for App {}
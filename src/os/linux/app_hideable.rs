@@ -0,0 +1,19 @@
+use log::info;
+
+use super::app::App;
+use super::x11::X11;
+use crate::os::Hideable;
+
+impl Hideable for App {
+    async fn hide(&self) -> anyhow::Result<()> {
+        info!("hiding app {self}");
+        let Some(ref entry) = self.entry else {
+            return Ok(()); // can't be running
+        };
+        let x11 = X11::connect(None)?;
+        for window in x11.find_windows(|window| entry.matches_window(window))? {
+            x11.iconify(window)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+use super::app::App;
+use super::desktop_entry::DesktopEntry;
+use super::icon_theme::find_icon;
+use super::x11::WindowIdentity;
+use super::xdg::{self, Locale};
+use crate::util::capitalize;
+
+const ICON_SIZE: u32 = 128;
+
+static ENTRY_CACHE: LazyLock<Mutex<EntryCache>> = LazyLock::new(Default::default);
+
+pub fn resolve(desktop_id: &str) -> App {
+    let entry = DesktopEntry::find(desktop_id, &xdg::data_dirs(), Locale::from_env().as_ref());
+    let name = entry
+        .as_ref()
+        .map(|entry| entry.name.clone())
+        .unwrap_or_else(|| heuristic_name(desktop_id));
+    let icon_path = entry
+        .as_ref()
+        .and_then(|entry| entry.icon.as_deref())
+        .and_then(|icon| find_icon(icon, ICON_SIZE, &xdg::icon_theme_name(), &xdg::icon_dirs()));
+    App::new(desktop_id.to_string(), entry, name, icon_path)
+}
+
+/// Entries with a `StartupWMClass` take precedence over guessed matches
+pub fn app_id_for_window(window: &WindowIdentity) -> Option<String> {
+    let data_dirs = xdg::data_dirs();
+    let mut cache = ENTRY_CACHE.lock().unwrap();
+    cache.refresh(&data_dirs);
+    let (exact, guessed): (Vec<_>, Vec<_>) = cache
+        .entries
+        .iter()
+        .partition(|entry| entry.startup_wm_class.is_some());
+    exact
+        .into_iter()
+        .chain(guessed)
+        .find(|entry| entry.matches_window(window))
+        .map(|entry| entry.id.clone())
+}
+
+fn heuristic_name(desktop_id: &str) -> String {
+    let stem = desktop_id.strip_suffix(".desktop").unwrap_or(desktop_id);
+    capitalize(stem.rsplit('.').next().unwrap_or(stem))
+}
+
+#[derive(Default)]
+struct EntryCache {
+    modified: Option<Vec<Option<SystemTime>>>,
+    entries: Vec<DesktopEntry>,
+}
+
+impl EntryCache {
+    /// Rescans when apps were (un)installed, which touches the applications dirs
+    fn refresh(&mut self, data_dirs: &[PathBuf]) {
+        let modified = data_dirs
+            .iter()
+            .map(|dir| {
+                std::fs::metadata(dir.join("applications"))
+                    .ok()?
+                    .modified()
+                    .ok()
+            })
+            .collect();
+        if self.modified.as_ref() != Some(&modified) {
+            self.entries = DesktopEntry::all(data_dirs, Locale::from_env().as_ref());
+            self.modified = Some(modified);
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use log::error;
+
+use super::app_metadata::app_id_for_window;
+use super::x11::X11;
+use crate::os::{AppObserver, System};
+
+impl AppObserver for System {
+    fn observe_app_activations() -> Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let result =
+                X11::connect(None).and_then(|x11| x11.forward_activations(tx, app_id_for_window));
+            if let Err(error) = result {
+                error!("stopped observing app activations: {error}");
+            }
+        });
+        rx
+    }
+}
@@ -0,0 +1,38 @@
+use std::process::{Command, Stdio};
+use std::thread;
+
+use anyhow::{Context, bail};
+use log::info;
+
+use super::app::App;
+use super::x11::X11;
+use crate::os::Openable;
+
+impl Openable for App {
+    async fn open(&self) -> anyhow::Result<()> {
+        info!("opening app {self}");
+        let Some(ref entry) = self.entry else {
+            bail!("could not find app with desktop id '{}'", self.desktop_id);
+        };
+        let x11 = X11::connect(None)?;
+        if let Some(window) = x11.find_window(|window| entry.matches_window(window))? {
+            return x11.activate(window);
+        }
+        let Some(argv) = entry.argv() else {
+            bail!("app with desktop id '{}' has no Exec key", self.desktop_id);
+        };
+        launch(&argv)
+    }
+}
+
+fn launch(argv: &[String]) -> anyhow::Result<()> {
+    let mut child = Command::new(&argv[0])
+        .args(&argv[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("could not launch '{}'", argv[0]))?;
+    thread::spawn(move || child.wait()); // reap once it exits
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use super::app_metadata::app_id_for_window;
+use super::x11::X11;
+use crate::os::{AppQuery, System};
+
+impl AppQuery for System {
+    fn current_app() -> anyhow::Result<Option<String>> {
+        let x11 = X11::connect(None)?;
+        match x11.active_window()? {
+            Some(window) => Ok(app_id_for_window(&x11.window_identity(window)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn running_apps() -> anyhow::Result<Vec<String>> {
+        let x11 = X11::connect(None)?;
+        let mut app_ids: Vec<String> = x11
+            .client_windows()?
+            .into_iter()
+            .filter_map(|window| x11.window_identity(window).ok()) // may be gone already
+            .filter_map(|identity| app_id_for_window(&identity))
+            .collect();
+        app_ids.sort();
+        app_ids.dedup();
+        Ok(app_ids)
+    }
+}
@@ -0,0 +1,30 @@
+use log::info;
+
+use super::app::App;
+use super::x11::X11;
+use crate::os::Quittable;
+
+impl Quittable for App {
+    fn is_running(&self) -> anyhow::Result<bool> {
+        let Some(ref entry) = self.entry else {
+            return Ok(false);
+        };
+        let x11 = X11::connect(None)?;
+        Ok(x11
+            .find_window(|window| entry.matches_window(window))?
+            .is_some())
+    }
+
+    /// Closes all windows, as X11 has no notion of quitting an app
+    async fn quit(&self) -> anyhow::Result<()> {
+        info!("quitting app {self}");
+        let Some(ref entry) = self.entry else {
+            return Ok(()); // can't be running
+        };
+        let x11 = X11::connect(None)?;
+        for window in x11.find_windows(|window| entry.matches_window(window))? {
+            x11.close(window)?;
+        }
+        Ok(())
+    }
+}
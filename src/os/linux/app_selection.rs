@@ -0,0 +1,23 @@
+use anyhow::Context;
+
+use super::desktop_entry::desktop_id;
+use super::xdg;
+use crate::os::{App, AppSelection, System};
+
+impl AppSelection for System {
+    async fn select_app() -> anyhow::Result<Option<App>> {
+        let Some(entry_path) = rfd::AsyncFileDialog::new()
+            .add_filter("Applications", &["desktop"])
+            .set_directory("/usr/share/applications")
+            .pick_file()
+            .await
+        else {
+            return Ok(None);
+        };
+        let desktop_id = xdg::data_dirs()
+            .iter()
+            .find_map(|dir| desktop_id(&dir.join("applications"), entry_path.path()))
+            .context("desktop entry is not in an applications directory")?;
+        Ok(Some(App::from(desktop_id)))
+    }
+}
@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+use crate::os::{ConfigDir, System};
+
+impl ConfigDir for System {
+    fn config_dir() -> PathBuf {
+        // Honors $XDG_CONFIG_HOME, falls back to ~/.config
+        dirs::config_dir().expect("could not determine config directory")
+    }
+}
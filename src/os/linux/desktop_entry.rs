@@ -0,0 +1,284 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::key_file::KeyFile;
+use super::x11::WindowIdentity;
+use super::xdg::Locale;
+
+const GROUP: &str = "Desktop Entry";
+const EXTENSION: &str = "desktop";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopEntry {
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub exec: Option<String>,
+    pub startup_wm_class: Option<String>,
+}
+
+impl DesktopEntry {
+    /// Returns `None` for anything that is not a visible application
+    pub fn parse(id: String, content: &str, locale: Option<&Locale>) -> Option<Self> {
+        let key_file = KeyFile::parse(content);
+        if key_file.get(GROUP, "Type") != Some("Application") || key_file.get_bool(GROUP, "Hidden")
+        {
+            return None;
+        }
+        let get = |key| key_file.get(GROUP, key).map(str::to_string);
+        Some(Self {
+            id,
+            name: key_file.get_localized(GROUP, "Name", locale)?.to_string(),
+            icon: get("Icon"),
+            exec: get("Exec"),
+            startup_wm_class: get("StartupWMClass"),
+        })
+    }
+
+    /// Entries in earlier data dirs shadow those with the same id in later ones
+    pub fn find(id: &str, data_dirs: &[PathBuf], locale: Option<&Locale>) -> Option<Self> {
+        data_dirs
+            .iter()
+            .flat_map(|dir| entry_paths(&dir.join("applications")))
+            .find(|(entry_id, _)| entry_id == id)
+            .and_then(|(id, path)| Self::parse(id, &fs::read_to_string(path).ok()?, locale))
+    }
+
+    pub fn all(data_dirs: &[PathBuf], locale: Option<&Locale>) -> Vec<Self> {
+        let mut seen = std::collections::HashSet::new();
+        data_dirs
+            .iter()
+            .flat_map(|dir| entry_paths(&dir.join("applications")))
+            .filter(|(id, _)| seen.insert(id.clone()))
+            .filter_map(|(id, path)| Self::parse(id, &fs::read_to_string(path).ok()?, locale))
+            .collect()
+    }
+
+    /// The `Exec` command line with field codes removed
+    pub fn argv(&self) -> Option<Vec<String>> {
+        let argv = split_exec(self.exec.as_deref()?);
+        (!argv.is_empty()).then_some(argv)
+    }
+
+    /// `StartupWMClass` is authoritative. Without it, we guess from the
+    /// desktop id and the binary, which usually match the window class.
+    pub fn matches_window(&self, window: &WindowIdentity) -> bool {
+        let wm_names = [&window.class, &window.instance];
+        if let Some(wm_class) = &self.startup_wm_class {
+            return wm_names.into_iter().flatten().any(|name| name == wm_class);
+        }
+        let stem = self.id.strip_suffix(".desktop").unwrap_or(&self.id);
+        let short_stem = stem.rsplit('.').next().unwrap_or(stem); // reverse DNS ids
+        let matches_stem = |name: &String| {
+            name.eq_ignore_ascii_case(stem) || name.eq_ignore_ascii_case(short_stem)
+        };
+        let window_binary = window.exe_path.as_deref().map(file_name);
+        let entry_binary = self.argv().map(|argv| file_name(&argv[0]).to_string());
+        wm_names.into_iter().flatten().any(matches_stem)
+            || (window_binary.is_some() && window_binary == entry_binary.as_deref())
+    }
+}
+
+/// Desktop file ids replace the path separators below `applications/` with dashes
+pub fn desktop_id(applications_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(applications_dir).ok()?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    Some(parts.join("-"))
+}
+
+fn entry_paths(applications_dir: &Path) -> Vec<(String, PathBuf)> {
+    fn walk(dir: &Path, paths: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for path in entries {
+            if path.is_dir() {
+                walk(&path, paths);
+            } else if path.extension().is_some_and(|ext| ext == EXTENSION) {
+                paths.push(path);
+            }
+        }
+    }
+    let mut paths = Vec::new();
+    walk(applications_dir, &mut paths);
+    paths
+        .into_iter()
+        .filter_map(|path| Some((desktop_id(applications_dir, &path)?, path)))
+        .collect()
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn split_exec(exec: &str) -> Vec<String> {
+    let mut argv = Vec::new();
+    let mut arg = String::new();
+    let mut has_arg = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                has_arg = true;
+            }
+            '\\' if quoted => arg.extend(chars.next()),
+            '%' => {
+                // Field codes like %U expand to files/urls we never pass
+                if chars.next() == Some('%') {
+                    arg.push('%');
+                    has_arg = true;
+                }
+            }
+            c if c.is_whitespace() && !quoted => {
+                if has_arg {
+                    argv.push(std::mem::take(&mut arg));
+                    has_arg = false;
+                }
+            }
+            c => {
+                arg.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        argv.push(arg);
+    }
+    argv
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn write(dir: &Path, relative: &str, content: &str) {
+        let path = dir.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn entry(name: &str, extra: &str) -> String {
+        format!("[Desktop Entry]\nType=Application\nName={name}\nExec={name} %U\n{extra}")
+    }
+
+    fn window(instance: &str, class: &str, exe_path: Option<&str>) -> WindowIdentity {
+        WindowIdentity {
+            instance: Some(instance.to_string()),
+            class: Some(class.to_string()),
+            exe_path: exe_path.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn find_in_fixture_data_dirs() {
+        // Arrange
+        let home = TempDir::new().unwrap();
+        let system = TempDir::new().unwrap();
+        write(
+            system.path(),
+            "applications/firefox.desktop",
+            &entry("System", ""),
+        );
+        write(
+            home.path(),
+            "applications/firefox.desktop",
+            &entry("User", ""),
+        );
+        write(
+            system.path(),
+            "applications/kde/kate.desktop",
+            &entry("Kate", ""),
+        );
+        let data_dirs = [home.path().to_path_buf(), system.path().to_path_buf()];
+
+        // Act
+        let firefox = DesktopEntry::find("firefox.desktop", &data_dirs, None);
+        let kate = DesktopEntry::find("kde-kate.desktop", &data_dirs, None);
+        let all = DesktopEntry::all(&data_dirs, None);
+
+        // Assert
+        assert_eq!(firefox.unwrap().name, "User");
+        assert_eq!(kate.unwrap().name, "Kate");
+        assert_eq!(all.len(), 2);
+    }
+
+    #[test]
+    fn parse_localized_name() {
+        // Arrange
+        let content = entry("Files", "Name[de]=Dateien\nName[de_CH]=Dateie\n");
+
+        // Act
+        let name = |locale: &str| {
+            let locale = Locale::parse(locale);
+            let entry = DesktopEntry::parse("f.desktop".into(), &content, locale.as_ref());
+            entry.unwrap().name
+        };
+
+        // Assert
+        assert_eq!(name("de_CH.UTF-8"), "Dateie");
+        assert_eq!(name("de_AT.UTF-8"), "Dateien");
+        assert_eq!(name("fr_FR.UTF-8"), "Files");
+        assert_eq!(name("C"), "Files");
+    }
+
+    #[test]
+    fn parse_skips_non_applications() {
+        let hidden = entry("Hidden", "Hidden=true\n");
+        let link = "[Desktop Entry]\nType=Link\nName=Link\n";
+
+        assert_eq!(DesktopEntry::parse("h.desktop".into(), &hidden, None), None);
+        assert_eq!(DesktopEntry::parse("l.desktop".into(), link, None), None);
+    }
+
+    #[test]
+    fn argv_removes_field_codes() {
+        let content = entry("x", "").replace(
+            "Exec=x %U",
+            r#"Exec="/opt/My App/app" --name "a \"b\"" %F 100%%"#,
+        );
+        let entry = DesktopEntry::parse("x.desktop".into(), &content, None).unwrap();
+
+        assert_eq!(
+            entry.argv().unwrap(),
+            vec!["/opt/My App/app", "--name", r#"a "b""#, "100%"]
+        );
+    }
+
+    #[test]
+    fn matches_window_by_startup_wm_class() {
+        let content = entry("chromium", "StartupWMClass=crx_abc\n");
+        let entry = DesktopEntry::parse("chrome-abc.desktop".into(), &content, None).unwrap();
+
+        assert!(entry.matches_window(&window("crx_abc", "Chromium", None)));
+        assert!(!entry.matches_window(&window("chromium", "Chromium", None)));
+    }
+
+    #[test]
+    fn matches_window_heuristically() {
+        let content = entry("firefox", "");
+        let entry = DesktopEntry::parse("org.mozilla.firefox.desktop".into(), &content, None);
+        let entry = entry.unwrap();
+
+        assert!(entry.matches_window(&window("Navigator", "firefox", None)));
+        assert!(entry.matches_window(&window("x", "y", Some("/usr/lib/firefox"))));
+        assert!(!entry.matches_window(&window("x", "y", Some("/usr/bin/other"))));
+    }
+
+    #[test]
+    fn desktop_id_from_path() {
+        let dir = Path::new("/usr/share/applications");
+
+        let id = desktop_id(dir, &dir.join("kde/org.kde.kate.desktop"));
+
+        assert_eq!(id.as_deref(), Some("kde-org.kde.kate.desktop"));
+    }
+}
@@ -0,0 +1,274 @@
+use std::path::{Path, PathBuf};
+
+use super::key_file::KeyFile;
+
+const FALLBACK_THEME: &str = "hicolor";
+const EXTENSIONS: [&str; 2] = ["png", "svg"]; // xpm can't be displayed by the webview
+
+/// Implements the lookup algorithm of the freedesktop icon theme specification
+pub fn find_icon(icon: &str, size: u32, theme: &str, base_dirs: &[PathBuf]) -> Option<PathBuf> {
+    if Path::new(icon).is_absolute() {
+        return Path::new(icon).exists().then(|| PathBuf::from(icon));
+    }
+    let mut visited = Vec::new();
+    find_in_theme(icon, size, theme, base_dirs, &mut visited)
+        .or_else(|| find_in_theme(icon, size, FALLBACK_THEME, base_dirs, &mut visited))
+        .or_else(|| find_unthemed(icon, base_dirs))
+}
+
+fn find_in_theme(
+    icon: &str,
+    size: u32,
+    theme_name: &str,
+    base_dirs: &[PathBuf],
+    visited: &mut Vec<String>,
+) -> Option<PathBuf> {
+    if visited.iter().any(|name| name == theme_name) {
+        return None; // guard against inheritance cycles
+    }
+    visited.push(theme_name.to_string());
+    let theme = Theme::load(theme_name, base_dirs)?;
+    theme.lookup(icon, size, base_dirs).or_else(|| {
+        theme
+            .parents
+            .iter()
+            .find_map(|parent| find_in_theme(icon, size, parent, base_dirs, visited))
+    })
+}
+
+fn find_unthemed(icon: &str, base_dirs: &[PathBuf]) -> Option<PathBuf> {
+    base_dirs.iter().find_map(|dir| existing_file(dir, icon))
+}
+
+fn existing_file(dir: &Path, icon: &str) -> Option<PathBuf> {
+    EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{icon}.{ext}")))
+        .find(|path| path.is_file())
+}
+
+struct Theme {
+    name: String,
+    parents: Vec<String>,
+    directories: Vec<ThemeDirectory>,
+}
+
+impl Theme {
+    fn load(name: &str, base_dirs: &[PathBuf]) -> Option<Self> {
+        let index = base_dirs
+            .iter()
+            .find_map(|dir| std::fs::read_to_string(dir.join(name).join("index.theme")).ok())?;
+        let index = KeyFile::parse(&index);
+        let group = "Icon Theme";
+        let directories = index
+            .get_list(group, "Directories")
+            .into_iter()
+            .chain(index.get_list(group, "ScaledDirectories"))
+            .filter_map(|path| ThemeDirectory::parse(&index, path))
+            .collect();
+        let parents = index
+            .get_list(group, "Inherits")
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        Some(Self {
+            name: name.to_string(),
+            parents,
+            directories,
+        })
+    }
+
+    fn lookup(&self, icon: &str, size: u32, base_dirs: &[PathBuf]) -> Option<PathBuf> {
+        let candidates = || {
+            self.directories.iter().flat_map(move |directory| {
+                base_dirs.iter().filter_map(move |base| {
+                    let dir = base.join(&self.name).join(&directory.path);
+                    Some((directory, existing_file(&dir, icon)?))
+                })
+            })
+        };
+        candidates()
+            .find(|(directory, _)| directory.matches_size(size))
+            .or_else(|| candidates().min_by_key(|(directory, _)| directory.size_distance(size)))
+            .map(|(_, path)| path)
+    }
+}
+
+enum DirectoryType {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+struct ThemeDirectory {
+    path: String,
+    kind: DirectoryType,
+    size: u32,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+}
+
+impl ThemeDirectory {
+    fn parse(index: &KeyFile, path: &str) -> Option<Self> {
+        let size = index.get_u32(path, "Size")?;
+        if index.get_u32(path, "Scale").unwrap_or(1) != 1 {
+            return None; // HiDPI variants
+        }
+        let kind = match index.get(path, "Type") {
+            Some("Fixed") => DirectoryType::Fixed,
+            Some("Scalable") => DirectoryType::Scalable,
+            _ => DirectoryType::Threshold,
+        };
+        Some(Self {
+            path: path.to_string(),
+            kind,
+            size,
+            min_size: index.get_u32(path, "MinSize").unwrap_or(size),
+            max_size: index.get_u32(path, "MaxSize").unwrap_or(size),
+            threshold: index.get_u32(path, "Threshold").unwrap_or(2),
+        })
+    }
+
+    fn matches_size(&self, size: u32) -> bool {
+        match self.kind {
+            DirectoryType::Fixed => self.size == size,
+            DirectoryType::Scalable => (self.min_size..=self.max_size).contains(&size),
+            DirectoryType::Threshold => {
+                let min = self.size.saturating_sub(self.threshold);
+                (min..=self.size + self.threshold).contains(&size)
+            }
+        }
+    }
+
+    fn size_distance(&self, size: u32) -> u32 {
+        let (min, max) = match self.kind {
+            DirectoryType::Fixed => (self.size, self.size),
+            DirectoryType::Scalable => (self.min_size, self.max_size),
+            DirectoryType::Threshold => (
+                self.size.saturating_sub(self.threshold),
+                self.size + self.threshold,
+            ),
+        };
+        min.saturating_sub(size) + size.saturating_sub(max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const HICOLOR_INDEX: &str = "[Icon Theme]
+Name=Hicolor
+Directories=48x48/apps,256x256/apps,scalable/apps
+
+[48x48/apps]
+Size=48
+Type=Threshold
+
+[256x256/apps]
+Size=256
+Type=Fixed
+
+[scalable/apps]
+Size=128
+MinSize=8
+MaxSize=512
+Type=Scalable
+";
+
+    const CUSTOM_INDEX: &str = "[Icon Theme]
+Name=Custom
+Inherits=hicolor
+Directories=128x128/apps
+
+[128x128/apps]
+Size=128
+Type=Fixed
+";
+
+    fn fixture(files: &[&str]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let icons = dir.path().join("icons");
+        for (theme, index) in [("hicolor", HICOLOR_INDEX), ("custom", CUSTOM_INDEX)] {
+            fs::create_dir_all(icons.join(theme)).unwrap();
+            fs::write(icons.join(theme).join("index.theme"), index).unwrap();
+        }
+        for file in files {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        dir
+    }
+
+    fn find(dir: &TempDir, icon: &str, theme: &str) -> Option<String> {
+        let base_dirs = [dir.path().join("icons"), dir.path().join("pixmaps")];
+        let path = find_icon(icon, 128, theme, &base_dirs)?;
+        Some(path.strip_prefix(dir.path()).unwrap().display().to_string())
+    }
+
+    #[test]
+    fn prefers_theme_over_hicolor() {
+        let dir = fixture(&[
+            "icons/custom/128x128/apps/app.png",
+            "icons/hicolor/scalable/apps/app.svg",
+        ]);
+
+        assert_eq!(
+            find(&dir, "app", "custom").as_deref(),
+            Some("icons/custom/128x128/apps/app.png")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_hicolor() {
+        let dir = fixture(&["icons/hicolor/scalable/apps/app.svg"]);
+
+        assert_eq!(
+            find(&dir, "app", "unknown-theme").as_deref(),
+            Some("icons/hicolor/scalable/apps/app.svg")
+        );
+    }
+
+    #[test]
+    fn prefers_matching_svg_over_closest_png() {
+        let dir = fixture(&[
+            "icons/hicolor/48x48/apps/app.png",
+            "icons/hicolor/scalable/apps/app.svg",
+        ]);
+
+        assert_eq!(
+            find(&dir, "app", "hicolor").as_deref(),
+            Some("icons/hicolor/scalable/apps/app.svg")
+        );
+    }
+
+    #[test]
+    fn picks_closest_size() {
+        let dir = fixture(&[
+            "icons/hicolor/48x48/apps/app.png",
+            "icons/hicolor/256x256/apps/app.png",
+        ]);
+
+        assert_eq!(
+            find(&dir, "app", "hicolor").as_deref(),
+            Some("icons/hicolor/48x48/apps/app.png") // 128 - 50 < 256 - 128
+        );
+    }
+
+    #[test]
+    fn falls_back_to_pixmaps() {
+        let dir = fixture(&["pixmaps/app.png"]);
+
+        assert_eq!(
+            find(&dir, "app", "hicolor").as_deref(),
+            Some("pixmaps/app.png")
+        );
+        assert_eq!(find(&dir, "missing", "hicolor"), None);
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use super::xdg::Locale;
+
+/// The ini-like format shared by desktop entries and icon theme indices
+#[derive(Debug, Default)]
+pub struct KeyFile {
+    groups: HashMap<String, HashMap<String, String>>,
+}
+
+impl KeyFile {
+    pub fn parse(content: &str) -> Self {
+        let mut groups: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut current = None;
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                current = Some(name.to_string());
+                groups.entry(name.to_string()).or_default();
+            } else if let Some(group) = &current
+                && let Some((key, value)) = line.split_once('=')
+            {
+                let entries = groups.get_mut(group).unwrap();
+                // The first occurrence of a key wins
+                entries
+                    .entry(key.trim().to_string())
+                    .or_insert_with(|| unescape(value.trim()));
+            }
+        }
+        Self { groups }
+    }
+
+    pub fn get(&self, group: &str, key: &str) -> Option<&str> {
+        self.groups.get(group)?.get(key).map(String::as_str)
+    }
+
+    pub fn get_localized(&self, group: &str, key: &str, locale: Option<&Locale>) -> Option<&str> {
+        locale
+            .into_iter()
+            .flat_map(Locale::candidates)
+            .find_map(|suffix| self.get(group, &format!("{key}[{suffix}]")))
+            .or_else(|| self.get(group, key))
+    }
+
+    pub fn get_bool(&self, group: &str, key: &str) -> bool {
+        self.get(group, key) == Some("true")
+    }
+
+    pub fn get_u32(&self, group: &str, key: &str) -> Option<u32> {
+        self.get(group, key)?.parse().ok()
+    }
+
+    pub fn get_list(&self, group: &str, key: &str) -> Vec<&str> {
+        self.get(group, key)
+            .map(|value| value.split([',', ';']).filter(|v| !v.is_empty()).collect())
+            .unwrap_or_default()
+    }
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => {
+                // Keep other escapes for the Exec key's own quoting rules
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
@@ -0,0 +1,146 @@
+mod activation;
+mod clients;
+mod connection;
+#[cfg(test)]
+mod xvfb;
+
+pub(super) use clients::WindowIdentity;
+pub(super) use connection::X11;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::xvfb::Xvfb;
+    use super::*;
+
+    fn current_exe() -> String {
+        std::env::current_exe().unwrap().display().to_string()
+    }
+
+    fn class(identity: &WindowIdentity) -> Option<String> {
+        identity.class.clone()
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb"]
+    fn active_window_identity() {
+        // Arrange
+        let xvfb = Xvfb::start();
+        let x11 = X11::connect(Some(xvfb.display())).unwrap();
+        let client = xvfb.client(Some(std::process::id()), "Throwaway");
+        xvfb.set_active_window(Some(client.window));
+
+        // Act
+        let window = x11.active_window().unwrap();
+        let identity = x11.window_identity(client.window).unwrap();
+
+        // Assert
+        assert_eq!(window, Some(client.window));
+        assert_eq!(
+            identity,
+            WindowIdentity {
+                instance: Some("throwaway".to_string()),
+                class: Some("Throwaway".to_string()),
+                exe_path: Some(current_exe()),
+            }
+        );
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb"]
+    fn window_identity_without_pid() {
+        // Arrange
+        let xvfb = Xvfb::start();
+        let x11 = X11::connect(Some(xvfb.display())).unwrap();
+        let client = xvfb.client(None, "Throwaway");
+
+        // Act
+        let identity = x11.window_identity(client.window).unwrap();
+
+        // Assert
+        assert_eq!(identity.exe_path, None);
+        assert_eq!(identity.class, Some("Throwaway".to_string()));
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb"]
+    fn no_active_window() {
+        // Arrange
+        let xvfb = Xvfb::start();
+        let x11 = X11::connect(Some(xvfb.display())).unwrap();
+        xvfb.set_active_window(None);
+
+        // Act
+        let window = x11.active_window().unwrap();
+
+        // Assert
+        assert_eq!(window, None);
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb"]
+    fn find_window() {
+        // Arrange
+        let xvfb = Xvfb::start();
+        let x11 = X11::connect(Some(xvfb.display())).unwrap();
+        let fst = xvfb.client(None, "Fst");
+        let snd = xvfb.client(None, "Snd");
+        xvfb.set_client_list(&[fst.window, snd.window]);
+
+        // Act
+        let found = x11.find_window(|w| class(w).as_deref() == Some("Snd"));
+        let missing = x11.find_window(|w| class(w).as_deref() == Some("Thd"));
+
+        // Assert
+        assert_eq!(found.unwrap(), Some(snd.window));
+        assert_eq!(missing.unwrap(), None);
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb"]
+    fn activate() {
+        // Arrange
+        let xvfb = Xvfb::start();
+        let x11 = X11::connect(Some(xvfb.display())).unwrap();
+        let client = xvfb.client(None, "Throwaway");
+        xvfb.redirect_client_messages();
+
+        // Act
+        x11.activate(client.window).unwrap();
+
+        // Assert
+        assert_eq!(
+            xvfb.next_client_message(),
+            (x11.atoms._NET_ACTIVE_WINDOW, client.window)
+        );
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb"]
+    fn forward_activations() {
+        // Arrange
+        let xvfb = Xvfb::start();
+        let x11 = X11::connect(Some(xvfb.display())).unwrap();
+        let fst = xvfb.client(None, "Fst");
+        let snd = xvfb.client(None, "Snd");
+        let (tx, rx) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        thread::spawn(move || {
+            x11.forward_activations_with(tx, class, || ready_tx.send(()).unwrap())
+        });
+        ready_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        // Act
+        xvfb.set_active_window(Some(fst.window));
+        xvfb.set_active_window(Some(fst.window)); // no duplicate activation
+        xvfb.set_active_window(Some(snd.window));
+
+        // Assert
+        let timeout = Duration::from_secs(5);
+        assert_eq!(rx.recv_timeout(timeout).unwrap(), "Fst");
+        assert_eq!(rx.recv_timeout(timeout).unwrap(), "Snd");
+    }
+}
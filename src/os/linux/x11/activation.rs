@@ -0,0 +1,91 @@
+use std::sync::mpsc::Sender;
+
+use x11rb::connection::Connection;
+use x11rb::protocol::Event;
+use x11rb::protocol::xproto::{
+    ChangeWindowAttributesAux, ClientMessageEvent, ConnectionExt, EventMask, Window,
+};
+
+use super::{WindowIdentity, X11};
+
+const SOURCE_PAGER: u32 = 2; // lets the WM know the request comes from the user
+const ICONIC_STATE: u32 = 3;
+
+impl X11 {
+    /// Asks the window manager to raise and focus the window
+    pub fn activate(&self, window: Window) -> anyhow::Result<()> {
+        let data = [SOURCE_PAGER, x11rb::CURRENT_TIME, 0, 0, 0];
+        let event = ClientMessageEvent::new(32, window, self.atoms._NET_ACTIVE_WINDOW, data);
+        let mask = EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY;
+        self.conn.send_event(false, self.root, mask, event)?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Asks the window manager to minimize the window
+    pub fn iconify(&self, window: Window) -> anyhow::Result<()> {
+        let data = [ICONIC_STATE, 0, 0, 0, 0];
+        let event = ClientMessageEvent::new(32, window, self.atoms.WM_CHANGE_STATE, data);
+        let mask = EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY;
+        self.conn.send_event(false, self.root, mask, event)?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Asks the window manager to close the window, like its close button would
+    pub fn close(&self, window: Window) -> anyhow::Result<()> {
+        let data = [x11rb::CURRENT_TIME, SOURCE_PAGER, 0, 0, 0];
+        let event = ClientMessageEvent::new(32, window, self.atoms._NET_CLOSE_WINDOW, data);
+        let mask = EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY;
+        self.conn.send_event(false, self.root, mask, event)?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Blocks and sends the app id whenever another app becomes active
+    pub fn forward_activations(
+        &self,
+        tx: Sender<String>,
+        identify: impl Fn(&WindowIdentity) -> Option<String>,
+    ) -> anyhow::Result<()> {
+        self.forward_activations_with(tx, identify, || ())
+    }
+
+    pub(super) fn forward_activations_with(
+        &self,
+        tx: Sender<String>,
+        identify: impl Fn(&WindowIdentity) -> Option<String>,
+        on_ready: impl FnOnce(),
+    ) -> anyhow::Result<()> {
+        let attributes = ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE);
+        self.conn
+            .change_window_attributes(self.root, &attributes)?
+            .check()?;
+        on_ready();
+        let mut last_app_id = None;
+        loop {
+            let Event::PropertyNotify(event) = self.conn.wait_for_event()? else {
+                continue;
+            };
+            if event.atom != self.atoms._NET_ACTIVE_WINDOW {
+                continue;
+            }
+            let Some(window) = self.active_window()? else {
+                continue;
+            };
+            let Ok(identity) = self.window_identity(window) else {
+                continue; // window may already be gone
+            };
+            let Some(app_id) = identify(&identity) else {
+                continue;
+            };
+            if last_app_id.as_ref() == Some(&app_id) {
+                continue;
+            }
+            if tx.send(app_id.clone()).is_err() {
+                return Ok(()); // receiver hung up
+            }
+            last_app_id = Some(app_id);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use x11rb::properties::WmClass;
+use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, Window};
+
+use super::X11;
+
+/// What a window tells about the app it belongs to
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WindowIdentity {
+    pub instance: Option<String>,
+    pub class: Option<String>,
+    pub exe_path: Option<String>,
+}
+
+impl X11 {
+    pub fn active_window(&self) -> anyhow::Result<Option<Window>> {
+        let windows =
+            self.u32_property(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW)?;
+        Ok(windows.first().copied().filter(|&w| w != x11rb::NONE))
+    }
+
+    pub fn client_windows(&self) -> anyhow::Result<Vec<Window>> {
+        self.u32_property(self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW)
+    }
+
+    pub fn window_identity(&self, window: Window) -> anyhow::Result<WindowIdentity> {
+        let (instance, class) = self.window_class(window)?.unzip();
+        let exe_path = self.window_pid(window)?.and_then(pid_to_exe);
+        Ok(WindowIdentity {
+            instance,
+            class,
+            exe_path,
+        })
+    }
+
+    pub fn find_window(
+        &self,
+        predicate: impl Fn(&WindowIdentity) -> bool,
+    ) -> anyhow::Result<Option<Window>> {
+        Ok(self.client_windows()?.into_iter().find(|&window| {
+            // windows may be destroyed while we iterate
+            self.window_identity(window)
+                .is_ok_and(|identity| predicate(&identity))
+        }))
+    }
+
+    pub fn find_windows(
+        &self,
+        predicate: impl Fn(&WindowIdentity) -> bool,
+    ) -> anyhow::Result<Vec<Window>> {
+        let mut windows = self.client_windows()?;
+        windows.retain(|&window| {
+            self.window_identity(window)
+                .is_ok_and(|identity| predicate(&identity))
+        });
+        Ok(windows)
+    }
+
+    fn window_pid(&self, window: Window) -> anyhow::Result<Option<u32>> {
+        let pids = self.u32_property(window, self.atoms._NET_WM_PID, AtomEnum::CARDINAL)?;
+        Ok(pids.first().copied())
+    }
+
+    fn window_class(&self, window: Window) -> anyhow::Result<Option<(String, String)>> {
+        let wm_class = WmClass::get(&self.conn, window)?.reply()?;
+        Ok(wm_class.map(|wm_class| {
+            let decode = |bytes| String::from_utf8_lossy(bytes).into_owned();
+            (decode(wm_class.instance()), decode(wm_class.class()))
+        }))
+    }
+
+    fn u32_property(
+        &self,
+        window: Window,
+        property: Atom,
+        type_: AtomEnum,
+    ) -> anyhow::Result<Vec<u32>> {
+        let reply = self
+            .conn
+            .get_property(false, window, property, type_, 0, u32::MAX)?
+            .reply()?;
+        Ok(reply.value32().map(Iterator::collect).unwrap_or_default())
+    }
+}
+
+fn pid_to_exe(pid: u32) -> Option<String> {
+    let exe_path = std::fs::read_link(format!("/proc/{pid}/exe")).ok()?;
+    let exe_path = exe_path.display().to_string();
+    // The binary may have been replaced by an update while running
+    Some(match exe_path.strip_suffix(" (deleted)") {
+        Some(stripped) => stripped.to_string(),
+        None => exe_path,
+    })
+}
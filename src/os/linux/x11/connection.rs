@@ -0,0 +1,31 @@
+use anyhow::Context;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::Window;
+use x11rb::rust_connection::RustConnection;
+
+x11rb::atom_manager! {
+    pub Atoms: AtomsCookie {
+        _NET_ACTIVE_WINDOW,
+        _NET_CLOSE_WINDOW,
+        _NET_CLIENT_LIST,
+        _NET_WM_PID,
+        WM_CHANGE_STATE,
+    }
+}
+
+pub struct X11 {
+    pub(super) conn: RustConnection,
+    pub(super) root: Window,
+    pub(super) atoms: Atoms,
+}
+
+impl X11 {
+    /// Connects to the given display, or to `$DISPLAY` if `None`
+    pub fn connect(display: Option<&str>) -> anyhow::Result<Self> {
+        let (conn, screen_num) =
+            x11rb::connect(display).context("could not connect to X server")?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = Atoms::new(&conn)?.reply()?;
+        Ok(Self { conn, root, atoms })
+    }
+}
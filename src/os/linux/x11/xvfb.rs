@@ -0,0 +1,148 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+use x11rb::connection::Connection;
+use x11rb::protocol::Event;
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ChangeWindowAttributesAux, ConnectionExt, CreateWindowAux, EventMask, PropMode,
+    Window, WindowClass,
+};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+use super::X11;
+
+/// A private X server for headless tests. There is no window manager,
+/// so the tests maintain the root window properties themselves.
+pub struct Xvfb {
+    process: Child,
+    display: String,
+    wm: X11,
+}
+
+/// An X client owning a single window, which is destroyed on drop
+pub struct ThrowawayClient {
+    _conn: RustConnection,
+    pub window: Window,
+}
+
+impl Xvfb {
+    /// Panics if Xvfb is not installed, as the tests using it are opt-in
+    pub fn start() -> Self {
+        let mut process = Command::new("Xvfb")
+            .args(["-displayfd", "1", "-nolisten", "tcp"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Xvfb is installed");
+        let mut display_number = String::new();
+        BufReader::new(process.stdout.take().unwrap())
+            .read_line(&mut display_number)
+            .unwrap();
+        let display = format!(":{}", display_number.trim());
+        let wm = X11::connect(Some(&display)).unwrap();
+        Self {
+            process,
+            display,
+            wm,
+        }
+    }
+
+    pub fn display(&self) -> &str {
+        &self.display
+    }
+
+    pub fn client(&self, pid: Option<u32>, class: &str) -> ThrowawayClient {
+        let (conn, screen_num) = x11rb::connect(Some(&self.display)).unwrap();
+        let screen = &conn.setup().roots[screen_num];
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            0,
+            window,
+            screen.root,
+            0,
+            0,
+            100,
+            100,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            screen.root_visual,
+            &CreateWindowAux::new(),
+        )
+        .unwrap();
+        let wm_class = format!("{}\0{class}\0", class.to_lowercase());
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            AtomEnum::WM_CLASS,
+            AtomEnum::STRING,
+            wm_class.as_bytes(),
+        )
+        .unwrap();
+        if let Some(pid) = pid {
+            conn.change_property32(
+                PropMode::REPLACE,
+                window,
+                self.wm.atoms._NET_WM_PID,
+                AtomEnum::CARDINAL,
+                &[pid],
+            )
+            .unwrap();
+        }
+        conn.sync().unwrap();
+        ThrowawayClient {
+            _conn: conn,
+            window,
+        }
+    }
+
+    pub fn set_active_window(&self, window: Option<Window>) {
+        let window = window.unwrap_or(x11rb::NONE);
+        self.set_root_windows(self.wm.atoms._NET_ACTIVE_WINDOW, &[window]);
+    }
+
+    pub fn set_client_list(&self, windows: &[Window]) {
+        self.set_root_windows(self.wm.atoms._NET_CLIENT_LIST, windows);
+    }
+
+    /// Intercepts client messages to the root window, like a window manager
+    pub fn redirect_client_messages(&self) {
+        let attributes =
+            ChangeWindowAttributesAux::new().event_mask(EventMask::SUBSTRUCTURE_REDIRECT);
+        self.wm
+            .conn
+            .change_window_attributes(self.wm.root, &attributes)
+            .unwrap()
+            .check()
+            .unwrap();
+    }
+
+    pub fn next_client_message(&self) -> (Atom, Window) {
+        loop {
+            if let Event::ClientMessage(event) = self.wm.conn.wait_for_event().unwrap() {
+                return (event.type_, event.window);
+            }
+        }
+    }
+
+    fn set_root_windows(&self, property: Atom, windows: &[Window]) {
+        self.wm
+            .conn
+            .change_property32(
+                PropMode::REPLACE,
+                self.wm.root,
+                property,
+                AtomEnum::WINDOW,
+                windows,
+            )
+            .unwrap();
+        self.wm.conn.sync().unwrap();
+    }
+}
+
+impl Drop for Xvfb {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
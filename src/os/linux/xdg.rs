@@ -0,0 +1,101 @@
+use std::env;
+use std::path::PathBuf;
+
+use super::key_file::KeyFile;
+
+const DEFAULT_DATA_DIRS: &str = "/usr/local/share:/usr/share";
+const FALLBACK_ICON_THEME: &str = "hicolor";
+
+/// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`, in order of preference
+pub fn data_dirs() -> Vec<PathBuf> {
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| DEFAULT_DATA_DIRS.to_string());
+    dirs::data_dir()
+        .into_iter()
+        .chain(
+            data_dirs
+                .split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        )
+        .collect()
+}
+
+/// Base directories of the icon theme specification
+pub fn icon_dirs() -> Vec<PathBuf> {
+    dirs::home_dir()
+        .map(|home| home.join(".icons"))
+        .into_iter()
+        .chain(data_dirs().into_iter().map(|dir| dir.join("icons")))
+        .chain([PathBuf::from("/usr/share/pixmaps")])
+        .collect()
+}
+
+/// The GTK icon theme, which most desktops keep in sync with their own setting
+pub fn icon_theme_name() -> String {
+    dirs::config_dir()
+        .and_then(|dir| std::fs::read_to_string(dir.join("gtk-3.0/settings.ini")).ok())
+        .and_then(|content| {
+            KeyFile::parse(&content)
+                .get("Settings", "gtk-icon-theme-name")
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| FALLBACK_ICON_THEME.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    lang: String,
+    country: Option<String>,
+    modifier: Option<String>,
+}
+
+impl Locale {
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::parse(&value))
+    }
+
+    /// Parses `lang_COUNTRY.ENCODING@MODIFIER`, where all but `lang` are optional
+    pub fn parse(value: &str) -> Option<Self> {
+        let (rest, modifier) = match value.split_once('@') {
+            Some((rest, modifier)) => (rest, Some(modifier.to_string())),
+            None => (value, None),
+        };
+        let rest = rest.split('.').next().unwrap_or(rest);
+        let (lang, country) = match rest.split_once('_') {
+            Some((lang, country)) => (lang, Some(country.to_string())),
+            None => (rest, None),
+        };
+        if lang.is_empty() || lang == "C" || lang == "POSIX" {
+            return None;
+        }
+        Some(Self {
+            lang: lang.to_string(),
+            country,
+            modifier,
+        })
+    }
+
+    /// Locale suffixes to try for localized keys, most specific first
+    pub fn candidates(&self) -> Vec<String> {
+        let lang = &self.lang;
+        let mut candidates = Vec::new();
+        if let (Some(country), Some(modifier)) = (&self.country, &self.modifier) {
+            candidates.push(format!("{lang}_{country}@{modifier}"));
+        }
+        if let Some(country) = &self.country {
+            candidates.push(format!("{lang}_{country}"));
+        }
+        if let Some(modifier) = &self.modifier {
+            candidates.push(format!("{lang}@{modifier}"));
+        }
+        candidates.push(lang.clone());
+        candidates
+    }
+}
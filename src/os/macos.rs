@@ -1,8 +1,10 @@
 mod app;
+mod app_hideable;
 mod app_metadata;
 mod app_observer;
 mod app_openable;
 mod app_query;
+mod app_quittable;
 mod app_selection;
 mod config_dir;
 mod edit_actions;
@@ -0,0 +1,17 @@
+use log::info;
+use objc2_app_kit::NSRunningApplication;
+use objc2_foundation::NSString;
+
+use super::app::App;
+use crate::os::Hideable;
+
+impl Hideable for App {
+    async fn hide(&self) -> anyhow::Result<()> {
+        info!("hiding app {self}");
+        let bundle_id = NSString::from_str(&self.bundle_id);
+        for app in NSRunningApplication::runningApplicationsWithBundleIdentifier(&bundle_id) {
+            app.hide();
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use objc2_app_kit::NSWorkspace;
+use objc2_app_kit::{NSApplicationActivationPolicy, NSWorkspace};
 
 use crate::os::{AppQuery, System};
 
@@ -9,4 +9,14 @@ impl AppQuery for System {
             .and_then(|app| app.bundleIdentifier())
             .map(|bid| bid.to_string()))
     }
+
+    fn running_apps() -> anyhow::Result<Vec<String>> {
+        Ok(NSWorkspace::sharedWorkspace()
+            .runningApplications()
+            .iter()
+            .filter(|app| app.activationPolicy() == NSApplicationActivationPolicy::Regular)
+            .filter_map(|app| app.bundleIdentifier())
+            .map(|bid| bid.to_string())
+            .collect())
+    }
 }
@@ -0,0 +1,23 @@
+use log::info;
+use objc2_app_kit::NSRunningApplication;
+use objc2_foundation::NSString;
+
+use super::app::App;
+use crate::os::Quittable;
+
+impl Quittable for App {
+    fn is_running(&self) -> anyhow::Result<bool> {
+        let bundle_id = NSString::from_str(&self.bundle_id);
+        let apps = NSRunningApplication::runningApplicationsWithBundleIdentifier(&bundle_id);
+        Ok(apps.count() > 0)
+    }
+
+    async fn quit(&self) -> anyhow::Result<()> {
+        info!("quitting app {self}");
+        let bundle_id = NSString::from_str(&self.bundle_id);
+        for app in NSRunningApplication::runningApplicationsWithBundleIdentifier(&bundle_id) {
+            app.terminate(); // returns whether the request was sent, not whether it quit
+        }
+        Ok(())
+    }
+}
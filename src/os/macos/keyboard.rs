@@ -41,13 +41,4 @@ impl Keyboard for System {
             (Modifiers::META, "⌘"),
         ]
     }
-
-    fn serde_modifier_format() -> ModifierFormat {
-        [
-            (Modifiers::META, "Cmd"),
-            (Modifiers::ALT, "Opt"),
-            (Modifiers::CONTROL, "Ctrl"),
-            (Modifiers::SHIFT, "Shift"),
-        ]
-    }
 }
@@ -0,0 +1,21 @@
+//! In-memory stand-in for the OS, scripted by tests through [`System`].
+//! State is per thread, so tests running in parallel don't interfere.
+
+mod app;
+mod app_hideable;
+mod app_observer;
+mod app_openable;
+mod app_query;
+mod app_quittable;
+mod app_selection;
+mod config_dir;
+#[cfg(target_os = "macos")]
+mod edit_actions;
+mod keyboard;
+mod state;
+
+pub use app::App;
+#[cfg(target_os = "macos")]
+pub use edit_actions::EditActions;
+
+pub struct System;
@@ -0,0 +1,71 @@
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Identifiable;
+use crate::os::AppMetadata;
+use crate::util::capitalize;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub struct App {
+    pub(super) app_id: String,
+    name: String,
+}
+
+impl Identifiable<String> for App {
+    fn id(&self) -> String {
+        self.app_id.clone()
+    }
+}
+
+impl AppMetadata for App {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn icon_path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+impl From<App> for String {
+    fn from(app: App) -> Self {
+        app.app_id
+    }
+}
+
+impl From<String> for App {
+    fn from(app_id: String) -> Self {
+        let name = capitalize(app_id.rsplit('.').next().unwrap_or(&app_id));
+        Self { app_id, name }
+    }
+}
+
+impl From<&str> for App {
+    fn from(app_id: &str) -> Self {
+        Self::from(app_id.to_string())
+    }
+}
+
+impl Display for App {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl Hash for App {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.app_id.hash(state);
+    }
+}
+
+impl PartialEq for App {
+    fn eq(&self, other: &Self) -> bool {
+        self.app_id == other.app_id
+    }
+}
+
+impl Eq for App {}
@@ -0,0 +1,13 @@
+use log::info;
+
+use super::app::App;
+use super::state::with_state;
+use crate::os::Hideable;
+
+impl Hideable for App {
+    async fn hide(&self) -> anyhow::Result<()> {
+        info!("hiding app {self}");
+        with_state(|state| state.hide(&self.app_id));
+        Ok(())
+    }
+}
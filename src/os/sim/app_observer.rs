@@ -0,0 +1,12 @@
+use std::sync::mpsc::{self, Receiver};
+
+use super::state::with_state;
+use crate::os::{AppObserver, System};
+
+impl AppObserver for System {
+    fn observe_app_activations() -> Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        with_state(|state| state.observers.push(tx));
+        rx
+    }
+}
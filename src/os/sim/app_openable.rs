@@ -0,0 +1,12 @@
+use log::info;
+
+use super::app::App;
+use super::state::with_state;
+use crate::os::Openable;
+
+impl Openable for App {
+    async fn open(&self) -> anyhow::Result<()> {
+        info!("opening app {self}");
+        with_state(|state| state.open(&self.app_id))
+    }
+}
@@ -0,0 +1,12 @@
+use super::state::with_state;
+use crate::os::{AppQuery, System};
+
+impl AppQuery for System {
+    fn current_app() -> anyhow::Result<Option<String>> {
+        Ok(with_state(|state| state.frontmost.clone()))
+    }
+
+    fn running_apps() -> anyhow::Result<Vec<String>> {
+        Ok(with_state(|state| state.running.iter().cloned().collect()))
+    }
+}
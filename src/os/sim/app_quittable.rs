@@ -0,0 +1,17 @@
+use log::info;
+
+use super::app::App;
+use super::state::with_state;
+use crate::os::Quittable;
+
+impl Quittable for App {
+    fn is_running(&self) -> anyhow::Result<bool> {
+        Ok(with_state(|state| state.running.contains(&self.app_id)))
+    }
+
+    async fn quit(&self) -> anyhow::Result<()> {
+        info!("quitting app {self}");
+        with_state(|state| state.quit(&self.app_id));
+        Ok(())
+    }
+}
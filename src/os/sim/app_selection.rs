@@ -0,0 +1,8 @@
+use super::state::with_state;
+use crate::os::{App, AppSelection, System};
+
+impl AppSelection for System {
+    async fn select_app() -> anyhow::Result<Option<App>> {
+        Ok(with_state(|state| state.selections.pop_front()).flatten())
+    }
+}
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+use super::state::with_state;
+use crate::os::{ConfigDir, System};
+
+impl ConfigDir for System {
+    fn config_dir() -> PathBuf {
+        // Never touch the real config unless a test asks for a specific dir
+        with_state(|state| state.config_dir.clone())
+            .unwrap_or_else(|| std::env::temp_dir().join("groupctrl-sim"))
+    }
+}
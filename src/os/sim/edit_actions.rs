@@ -0,0 +1,9 @@
+use crate::os::System;
+
+pub trait EditActions {
+    fn select_all();
+}
+
+impl EditActions for System {
+    fn select_all() {}
+}
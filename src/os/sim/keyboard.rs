@@ -0,0 +1,26 @@
+use global_hotkey::hotkey::{Code, Modifiers};
+
+use crate::os::{Keyboard, ModifierFormat, System};
+
+impl Keyboard for System {
+    fn is_multi_select(modifiers: Modifiers) -> bool {
+        modifiers.ctrl()
+    }
+
+    fn show_parts_sep() -> &'static str {
+        "+"
+    }
+
+    fn show_key(_key: Code) -> Option<String> {
+        None
+    }
+
+    fn show_modifier_format() -> ModifierFormat {
+        [
+            (Modifiers::CONTROL, "Ctrl"),
+            (Modifiers::META, "Super"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+        ]
+    }
+}
@@ -0,0 +1,128 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+use anyhow::bail;
+
+use crate::os::{App, System};
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+pub(super) fn with_state<T>(f: impl FnOnce(&mut State) -> T) -> T {
+    STATE.with_borrow_mut(f)
+}
+
+#[derive(Default)]
+pub(super) struct State {
+    pub(super) installed: HashSet<String>,
+    pub(super) running: HashSet<String>,
+    pub(super) frontmost: Option<String>,
+    pub(super) opened: Vec<String>,
+    pub(super) hidden: Vec<String>,
+    pub(super) quit: Vec<String>,
+    pub(super) open_failures: HashMap<String, String>,
+    pub(super) selections: VecDeque<Option<App>>,
+    pub(super) observers: Vec<Sender<String>>,
+    pub(super) config_dir: Option<PathBuf>,
+}
+
+impl State {
+    pub(super) fn open(&mut self, app_id: &str) -> anyhow::Result<()> {
+        self.opened.push(app_id.to_string());
+        if let Some(message) = self.open_failures.get(app_id) {
+            bail!("{message}");
+        }
+        if !self.installed.contains(app_id) && !self.running.contains(app_id) {
+            bail!("could not find app '{app_id}'");
+        }
+        self.activate(app_id);
+        Ok(())
+    }
+
+    pub(super) fn hide(&mut self, app_id: &str) {
+        if !self.running.contains(app_id) {
+            return;
+        }
+        self.hidden.push(app_id.to_string());
+        if self.frontmost.as_deref() == Some(app_id) {
+            self.frontmost = None;
+        }
+    }
+
+    pub(super) fn quit(&mut self, app_id: &str) {
+        if !self.running.remove(app_id) {
+            return;
+        }
+        self.quit.push(app_id.to_string());
+        if self.frontmost.as_deref() == Some(app_id) {
+            self.frontmost = None;
+        }
+    }
+
+    fn activate(&mut self, app_id: &str) {
+        self.running.insert(app_id.to_string());
+        if self.frontmost.as_deref() == Some(app_id) {
+            return;
+        }
+        self.frontmost = Some(app_id.to_string());
+        self.observers
+            .retain(|observer| observer.send(app_id.to_string()).is_ok());
+    }
+}
+
+/// Scripting interface for tests
+#[allow(dead_code)]
+impl System {
+    /// Makes the app launchable without running it
+    pub fn install(app_id: &str) {
+        with_state(|state| state.installed.insert(app_id.to_string()));
+    }
+
+    /// Launches the app and brings it to the front, as if the user did
+    pub fn activate(app_id: &str) {
+        with_state(|state| state.activate(app_id));
+    }
+
+    pub fn frontmost() -> Option<String> {
+        with_state(|state| state.frontmost.clone())
+    }
+
+    pub fn is_running(app_id: &str) -> bool {
+        with_state(|state| state.running.contains(app_id))
+    }
+
+    /// All app ids passed to `Openable::open`, in order
+    pub fn opened() -> Vec<String> {
+        with_state(|state| state.opened.clone())
+    }
+
+    /// All running app ids passed to `Hideable::hide`, in order
+    pub fn hidden() -> Vec<String> {
+        with_state(|state| state.hidden.clone())
+    }
+
+    /// All running app ids passed to `Quittable::quit`, in order
+    pub fn quit() -> Vec<String> {
+        with_state(|state| state.quit.clone())
+    }
+
+    pub fn fail_open(app_id: &str, message: &str) {
+        with_state(|state| {
+            state
+                .open_failures
+                .insert(app_id.to_string(), message.to_string())
+        });
+    }
+
+    /// Queues the result of the next `AppSelection::select_app`
+    pub fn queue_selection(app: Option<App>) {
+        with_state(|state| state.selections.push_back(app));
+    }
+
+    pub fn set_config_dir(dir: PathBuf) {
+        with_state(|state| state.config_dir = Some(dir));
+    }
+}
@@ -11,7 +11,6 @@ pub trait Keyboard {
     fn show_parts_sep() -> &'static str;
     fn show_key(key: Code) -> Option<String>;
     fn show_modifier_format() -> ModifierFormat;
-    fn serde_modifier_format() -> ModifierFormat;
 }
 
 pub trait ConfigDir {
@@ -20,6 +19,8 @@ pub trait ConfigDir {
 
 pub trait AppQuery {
     fn current_app() -> anyhow::Result<Option<String>>;
+    /// Ids of the apps that are running, as far as they can be found
+    fn running_apps() -> anyhow::Result<Vec<String>>;
 }
 
 pub trait AppSelection {
@@ -30,6 +31,18 @@ pub trait Openable {
     async fn open(&self) -> anyhow::Result<()>;
 }
 
+pub trait Hideable {
+    /// Hides all windows of the app, doing nothing if it isn't running
+    async fn hide(&self) -> anyhow::Result<()>;
+}
+
+pub trait Quittable {
+    /// Whether the app has a process or window that can be found
+    fn is_running(&self) -> anyhow::Result<bool>;
+    /// Asks the app to quit, which it may refuse, doing nothing if it isn't running
+    async fn quit(&self) -> anyhow::Result<()>;
+}
+
 pub trait AppMetadata {
     fn name(&self) -> &str;
     fn icon_path(&self) -> Option<&Path>;
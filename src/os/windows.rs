@@ -1,7 +1,9 @@
 mod app;
+mod app_hideable;
 mod app_observer;
 mod app_openable;
 mod app_query;
+mod app_quittable;
 mod app_selection;
 mod config_dir;
 mod keyboard;
@@ -0,0 +1,8 @@
+use super::app_openable::minimize;
+use crate::os::{App, Hideable};
+
+impl Hideable for App {
+    async fn hide(&self) -> anyhow::Result<()> {
+        minimize(&self.exe_path)
+    }
+}
@@ -3,6 +3,8 @@ use crate::os::{App, Openable};
 mod open_app;
 mod win32;
 
+pub(super) use win32::{close, has_main_window, minimize, running_exes};
+
 impl Openable for App {
     async fn open(&self) -> anyhow::Result<()> {
         open_app::open_app(&self.exe_path)
@@ -1,6 +1,12 @@
 mod api;
+mod close;
 mod focus;
+mod minimize;
 mod pid_to_exe;
 mod windows;
 
+pub(in crate::os) use close::close;
 pub(super) use focus::focus;
+pub(in crate::os) use minimize::minimize;
+
+pub(in crate::os) use self::windows::{has_main_window, running_exes};
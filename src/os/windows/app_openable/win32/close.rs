@@ -0,0 +1,17 @@
+use super::api as win32;
+use super::windows::main_windows_of;
+
+/// Asks every main window of the app to close, like its close button would
+pub fn close(exe_path: &str) -> anyhow::Result<()> {
+    for window in main_windows_of(exe_path)? {
+        unsafe {
+            win32::PostMessageW(
+                Some(window),
+                win32::WM_CLOSE,
+                win32::WPARAM(0),
+                win32::LPARAM(0),
+            )?;
+        }
+    }
+    Ok(())
+}
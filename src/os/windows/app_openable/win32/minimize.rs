@@ -0,0 +1,13 @@
+use super::api as win32;
+use super::windows::main_windows_of;
+
+/// Minimizes every main window of the app, as Windows has no notion of hiding an app
+pub fn minimize(exe_path: &str) -> anyhow::Result<()> {
+    for window in main_windows_of(exe_path)? {
+        unsafe {
+            // returns whether the window was visible before, not an error
+            let _ = win32::ShowWindow(window, win32::SW_MINIMIZE);
+        }
+    }
+    Ok(())
+}
@@ -51,3 +51,37 @@ pub(super) fn find_matching_window(
     }
     Ok(None)
 }
+
+/// The main windows of every process running the executable
+pub(super) fn main_windows_of(exe_path: &str) -> windows::core::Result<Vec<win32::HWND>> {
+    let mut windows = collect_main_windows()?;
+    windows.retain(|&window| {
+        let mut process_id = 0u32;
+        unsafe {
+            win32::GetWindowThreadProcessId(window, Some(&mut process_id));
+        }
+        pid_to_exe(process_id).is_ok_and(|exe| exe.eq_ignore_ascii_case(exe_path))
+    });
+    Ok(windows)
+}
+
+pub fn has_main_window(exe_path: &str) -> anyhow::Result<bool> {
+    Ok(!main_windows_of(exe_path)?.is_empty())
+}
+
+/// Executables of the processes that have a main window
+pub fn running_exes() -> anyhow::Result<Vec<String>> {
+    let mut exes = Vec::new();
+    for window in collect_main_windows()? {
+        let mut process_id = 0u32;
+        unsafe {
+            win32::GetWindowThreadProcessId(window, Some(&mut process_id));
+        }
+        if let Ok(exe) = pid_to_exe(process_id) {
+            exes.push(exe);
+        }
+    }
+    exes.sort();
+    exes.dedup();
+    Ok(exes)
+}
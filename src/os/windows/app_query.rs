@@ -1,7 +1,12 @@
+use super::app_openable::running_exes;
 use crate::os::{AppQuery, System};
 
 impl AppQuery for System {
     fn current_app() -> anyhow::Result<Option<String>> {
         Ok(None)
     }
+
+    fn running_apps() -> anyhow::Result<Vec<String>> {
+        running_exes()
+    }
 }
@@ -0,0 +1,15 @@
+use log::info;
+
+use super::app_openable::{close, has_main_window};
+use crate::os::{App, Quittable};
+
+impl Quittable for App {
+    fn is_running(&self) -> anyhow::Result<bool> {
+        has_main_window(&self.exe_path)
+    }
+
+    async fn quit(&self) -> anyhow::Result<()> {
+        info!("quitting app {self}");
+        close(&self.exe_path)
+    }
+}
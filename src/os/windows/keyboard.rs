@@ -23,13 +23,4 @@ impl Keyboard for System {
             (Modifiers::SHIFT, "Shift"),
         ]
     }
-
-    fn serde_modifier_format() -> ModifierFormat {
-        [
-            (Modifiers::CONTROL, "Ctrl"),
-            (Modifiers::META, "Win"),
-            (Modifiers::ALT, "Alt"),
-            (Modifiers::SHIFT, "Shift"),
-        ]
-    }
 }
@@ -1,10 +1,16 @@
 mod action_service;
+mod clock;
 mod config_reader;
 mod config_service;
+mod config_watcher;
 mod group_service;
 mod hotkey_service;
 
 pub use action_service::ActionService;
+pub use clock::{Clock, SystemClock};
 pub use config_reader::ConfigReader;
 pub use config_service::ConfigService;
-pub use hotkey_service::HotkeyService;
+pub use config_watcher::ConfigWatcher;
+pub use hotkey_service::{
+    CHORD_TIMEOUT, HotkeyBackend, HotkeyBindError, HotkeyService, start_event_loop,
+};
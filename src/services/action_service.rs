@@ -1,6 +1,18 @@
-use crate::models::Action;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, bail};
+use log::{error, info, warn};
+use uuid::Uuid;
+
+use crate::models::{Action, Direction, SequenceStep};
+use crate::os::App;
 use crate::services::ConfigReader;
 use crate::services::group_service::GroupService;
+use crate::util::sleep;
 
 #[derive(Clone)]
 pub struct ActionService {
@@ -13,9 +25,165 @@ impl ActionService {
             group_service: GroupService::new(config_reader),
         }
     }
+
+    /// Errors are logged, as there is no one to report them to
     pub async fn execute(&self, action: &Action) {
+        if let Err(error) = self.try_execute(action).await {
+            error!("Could not execute {action:?} due to the following error: {error:#}");
+        }
+    }
+
+    /// For callers that can report errors, like the UI
+    pub async fn try_execute(&self, action: &Action) -> anyhow::Result<()> {
+        match action {
+            Action::Sequence {
+                steps,
+                stop_on_error,
+            } => self.execute_sequence(steps, *stop_on_error).await,
+            action => self.execute_step(action).await,
+        }
+    }
+
+    async fn execute_sequence(
+        &self,
+        steps: &[SequenceStep],
+        stop_on_error: bool,
+    ) -> anyhow::Result<()> {
+        for (i, step) in steps.iter().enumerate() {
+            if let Some(delay_ms) = step.delay_ms {
+                sleep(Duration::from_millis(delay_ms)).await;
+            }
+            let number = i + 1;
+            match self.execute_step(&step.action).await {
+                Ok(()) => info!("step {number} of sequence succeeded: {:?}", step.action),
+                Err(error) if stop_on_error => {
+                    return Err(error.context(format!("step {number} of sequence failed")));
+                }
+                Err(error) => warn!("step {number} of sequence failed, continuing: {error:#}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// See `GroupService::rule_matches`
+    pub fn rule_matches(&self, group_id: Uuid) -> Vec<(String, Vec<App>)> {
+        self.group_service
+            .rule_matches(group_id)
+            .inspect_err(|error| error!("could not match rules: {error}"))
+            .unwrap_or_default()
+    }
+
+    /// Runs any action except sequences, which can't be nested
+    async fn execute_step(&self, action: &Action) -> anyhow::Result<()> {
         match action {
-            Action::OpenGroup { group_id } => self.group_service.open(*group_id).await,
+            Action::OpenGroup { group_id } => {
+                self.group_service.open(*group_id, Direction::Forward).await
+            }
+            Action::CycleGroup {
+                group_id,
+                direction,
+            } => self.group_service.open(*group_id, *direction).await,
+            Action::OpenTarget { group_id } => self.group_service.open_target(*group_id).await,
+            Action::LaunchAllApps { group_id } => self.group_service.launch_all(*group_id).await,
+            Action::QuitAllApps { group_id } => self.group_service.quit_all(*group_id).await,
+            Action::LaunchApp { app } => {
+                let app = app.app().context("the app has no id for this platform")?;
+                GroupService::open_app(app).await
+            }
+            Action::FocusPreviousApp => self.group_service.open_previous().await,
+            Action::OpenUrl { url } => {
+                info!("opening url {url}");
+                open::that_detached(url).with_context(|| format!("could not open url '{url}'"))
+            }
+            Action::RunCommand { argv, cwd, env } => run_command(argv, cwd.as_deref(), env),
+            Action::ShowGroupCtrlWindow
+            | Action::ReloadConfig
+            | Action::SwitchProfile { .. }
+            | Action::StartChord { .. } => bail!("{action:?} must be handled by the UI"),
+            Action::Sequence { .. } => bail!("sequences can't be nested"),
+        }
+    }
+}
+
+fn run_command(
+    argv: &[String],
+    cwd: Option<&Path>,
+    env: &BTreeMap<String, String>,
+) -> anyhow::Result<()> {
+    let (program, args) = argv.split_first().context("command is empty")?;
+    info!("running command {argv:?}");
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .envs(env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("could not launch '{program}'"))?;
+    thread::spawn(move || child.wait()); // reap once it exits
+    Ok(())
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::models::Config;
+    use crate::os::{App, System};
+
+    const SAFARI: &str = "com.apple.Safari";
+    const MISSING: &str = "com.example.Missing";
+
+    fn launch_sequence(stop_on_error: bool) -> Action {
+        let step = |app_id| SequenceStep {
+            delay_ms: None,
+            action: Action::LaunchApp {
+                app: Box::new(App::from(app_id).into()),
+            },
+        };
+        Action::Sequence {
+            steps: vec![step(MISSING), step(SAFARI)],
+            stop_on_error,
         }
     }
+
+    fn setup() -> ActionService {
+        System::install(SAFARI);
+        let config_reader = ConfigReader::new(Arc::new(RwLock::new(Config::default())));
+        ActionService::new(config_reader)
+    }
+
+    #[test]
+    fn sequence_continues_after_error() {
+        // Arrange
+        let service = setup();
+
+        // Act
+        block_on(service.execute(&launch_sequence(false)));
+
+        // Assert
+        assert_eq!(System::opened(), vec![MISSING, SAFARI]);
+        assert_eq!(System::frontmost().as_deref(), Some(SAFARI));
+    }
+
+    #[test]
+    fn sequence_stops_on_error() {
+        // Arrange
+        let service = setup();
+
+        // Act
+        block_on(service.execute(&launch_sequence(true)));
+
+        // Assert
+        assert_eq!(System::opened(), vec![MISSING]);
+        assert_eq!(System::frontmost(), None);
+    }
 }
@@ -0,0 +1,41 @@
+use std::time::Instant;
+
+/// Source of time, so timing-dependent behavior can be tested deterministically
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Only moves forward when told to
+    pub struct ManualClock(Mutex<Instant>);
+
+    impl ManualClock {
+        pub fn new() -> Self {
+            Self(Mutex::new(Instant::now()))
+        }
+
+        pub fn advance(&self, duration: Duration) {
+            *self.0.lock().unwrap() += duration;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+}
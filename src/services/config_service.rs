@@ -1,30 +1,50 @@
 use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use dioxus::hooks::UnboundedSender;
+use log::{error, warn};
 use uuid::Uuid;
 
-use crate::models::{Action, Bindable, Config, DuplicateGroupName, Hotkey};
+use crate::models::{
+    Action, Backup, Bindable, Config, ConfigLoadError, CycleOrder, DuplicateGroupName, Hotkey,
+    RepeatPolicy, ReverseHotkey,
+};
 use crate::os::App;
-use crate::services::HotkeyService;
 use crate::services::config_reader::ConfigReader;
 use crate::services::hotkey_service::HotkeyBindError;
+use crate::services::{HotkeyBackend, HotkeyService};
 
 pub struct ConfigService {
     config: Arc<RwLock<Config>>,
     hotkey_service: HotkeyService,
+    load_error: Option<ConfigLoadError>, // saving is disabled while set
+    save_error: Option<String>,          // of the last save, which left the file as it was
+    handed_over: bool,                   // to another process, which writes the config meanwhile
 }
 
 impl ConfigService {
+    /// Loads the config from disk into the shared `config`
     pub fn new(
         config: Arc<RwLock<Config>>,
         hotkey_sender: UnboundedSender<(Hotkey, Action)>,
+        hotkey_backend: HotkeyBackend,
     ) -> Self {
+        let load_error = Self::load_into(&config).err();
+        let config_reader = ConfigReader::new(config.clone());
         Self {
-            config: config.clone(),
-            hotkey_service: HotkeyService::new(ConfigReader::new(config), hotkey_sender),
+            config,
+            hotkey_service: HotkeyService::new(config_reader, hotkey_sender, hotkey_backend),
+            load_error,
+            save_error: None,
+            handed_over: false,
         }
     }
 
+    fn load_into(config: &RwLock<Config>) -> Result<(), ConfigLoadError> {
+        let loaded = Config::load().inspect_err(|e| error!("could not load config: {e}"))?;
+        *config.write().unwrap() = loaded;
+        Ok(())
+    }
+
     // This is reactive and intended for usage in Dioxus
     pub fn config(&self) -> RwLockReadGuard<'_, Config> {
         self.config.read().unwrap()
@@ -34,8 +54,69 @@ impl ConfigService {
         self.config.write().unwrap()
     }
 
-    fn save(&self) {
-        self.config().save().unwrap();
+    fn save(&mut self) {
+        if self.load_error.is_some() {
+            warn!("not saving config until the load error is resolved");
+            return;
+        }
+        if self.handed_over {
+            warn!("not saving config, as another process writes it");
+            return;
+        }
+        let saved = self.config().save();
+        self.save_error = saved.err().map(|e| format!("{e:#}"));
+        if let Some(error) = &self.save_error {
+            error!("could not save config: {error}");
+        }
+    }
+
+    pub fn load_error(&self) -> Option<&ConfigLoadError> {
+        self.load_error.as_ref()
+    }
+
+    /// Changes stay in memory after a failed save, and are saved with the next one
+    pub fn save_error(&self) -> Option<&str> {
+        self.save_error.as_deref()
+    }
+
+    /// While another process writes the config, whose saves are picked up by
+    /// reloading, edits made here stay in memory
+    pub fn set_handed_over(&mut self, handed_over: bool) {
+        self.handed_over = handed_over;
+    }
+
+    pub fn is_handed_over(&self) -> bool {
+        self.handed_over
+    }
+
+    /// Picks up edits made to the file by hand. If the file doesn't load, the
+    /// current config is kept, but not saved over the file until it is fixed.
+    pub fn reload(&mut self) -> Result<(), &ConfigLoadError> {
+        let previous = self.config().bindings();
+        match Self::load_into(&self.config) {
+            Ok(()) => {
+                self.load_error = None;
+                self.hotkey_service.rebind_changed(previous);
+                Ok(())
+            }
+            Err(error) => Err(self.load_error.insert(error)),
+        }
+    }
+
+    pub fn restore_backup(&mut self, backup: &Backup) -> Result<(), ConfigLoadError> {
+        let previous = self.config().bindings();
+        *self.config_mut() = Config::restore_backup(backup)?;
+        self.load_error = None;
+        self.hotkey_service.rebind_changed(previous);
+        Ok(())
+    }
+
+    /// Replaces a broken config, which remains available as the quarantine copy
+    pub fn reset(&mut self) {
+        self.hotkey_service.unbind_all();
+        *self.config_mut() = Config::default();
+        self.load_error = None;
+        self.save();
     }
 
     pub fn add_group(&mut self, name: String) -> Uuid {
@@ -45,9 +126,9 @@ impl ConfigService {
     }
 
     pub fn remove_group(&mut self, group_id: Uuid) {
-        let hotkey = self.config().group(group_id).unwrap().hotkey;
-        self.hotkey_service.unbind_hotkey(hotkey);
+        let previous = self.config().bindings();
         self.config_mut().remove_group(group_id);
+        self.hotkey_service.rebind_changed(previous);
         self.save();
     }
 
@@ -57,6 +138,24 @@ impl ConfigService {
         Ok(())
     }
 
+    pub fn set_cycle(&mut self, group_id: Uuid, cycle: CycleOrder) {
+        self.config_mut().set_cycle(group_id, cycle);
+        self.save();
+    }
+
+    pub fn set_on_repeat(&mut self, group_id: Uuid, on_repeat: RepeatPolicy) {
+        self.config_mut().set_on_repeat(group_id, on_repeat);
+        self.save();
+    }
+
+    /// Binds or unbinds the gestures derived from the group hotkey
+    pub fn set_gestures(&mut self, group_id: Uuid, gestures: bool) {
+        let previous = self.config().bindings();
+        self.config_mut().set_gestures(group_id, gestures);
+        self.hotkey_service.rebind_changed(previous);
+        self.save();
+    }
+
     pub fn set_target(&mut self, group_id: Uuid, app: Option<App>) {
         self.config_mut().set_target(group_id, app);
         self.save();
@@ -77,11 +176,146 @@ impl ConfigService {
         group_id: Uuid,
         hotkey: Option<Hotkey>,
     ) -> Result<(), HotkeyBindError> {
-        let (existing_hotkey, action) = self.config().group(group_id).unwrap().binding();
+        let unknown = |_| HotkeyBindError::UnknownGroup { group_id };
+        let (existing_hotkey, action) = self.config().group(group_id).map_err(unknown)?.binding();
+        self.rebind(hotkey, existing_hotkey, action, |config| {
+            config.set_hotkey(group_id, hotkey)
+        })
+    }
+
+    pub fn set_reverse_hotkey(
+        &mut self,
+        group_id: Uuid,
+        reverse_hotkey: ReverseHotkey,
+    ) -> Result<(), HotkeyBindError> {
+        let unknown = |_| HotkeyBindError::UnknownGroup { group_id };
+        let group_hotkey = self.config().group(group_id).map_err(unknown)?.hotkey;
+        let hotkey = reverse_hotkey.resolve(group_hotkey);
+        let (existing_hotkey, action) = self.config().reverse_binding(group_id).map_err(unknown)?;
+        self.rebind(hotkey, existing_hotkey, action, |config| {
+            config.set_reverse_hotkey(group_id, reverse_hotkey)
+        })
+    }
+
+    /// Only rebinds the hotkeys that differ between the profiles
+    pub fn switch_profile(&mut self, profile_id: Option<Uuid>) {
+        let previous = self.config().bindings();
+        self.config_mut().set_active_profile(profile_id);
+        self.hotkey_service.rebind_changed(previous);
+        self.save();
+    }
+
+    /// See `HotkeyService::start_chord`
+    pub fn start_chord(&mut self, leader: Hotkey) -> Result<u64, HotkeyBindError> {
+        self.hotkey_service.start_chord(leader)
+    }
+
+    pub fn end_chord(&mut self) {
+        self.hotkey_service.end_chord();
+    }
+
+    pub fn expire_chord(&mut self, id: u64) {
+        self.hotkey_service.expire_chord(id);
+    }
+
+    /// Binds `hotkey` before applying `update`, after which hotkeys derived
+    /// from it follow, e.g. the reverse hotkey of a group. Groups outside the
+    /// active profile are only checked, see `Config::profile_bindings`, and
+    /// bound once a profile that enables them is switched to.
+    fn rebind(
+        &mut self,
+        hotkey: Option<Hotkey>,
+        existing_hotkey: Option<Hotkey>,
+        action: Action,
+        update: impl FnOnce(&mut Config),
+    ) -> Result<(), HotkeyBindError> {
+        if let Some(group_id) = action.group_id()
+            && !self.config().is_enabled(group_id)
+        {
+            if let Some(hk) = hotkey.filter(|_| hotkey != existing_hotkey) {
+                let bindings = self.config().profile_bindings(group_id);
+                self.hotkey_service.check_conflict(hk, &action, &bindings)?;
+            }
+            update(&mut self.config_mut());
+            self.save();
+            return Ok(());
+        }
+        let previous = self.config().bindings();
         self.hotkey_service
-            .bind_hotkey(hotkey, existing_hotkey, action)?;
-        self.config_mut().set_hotkey(group_id, hotkey);
+            .bind_hotkey(hotkey, existing_hotkey, action.clone())?;
+        update(&mut self.config_mut());
+        let previous = previous
+            .into_iter()
+            .filter(|(_, a)| *a != action)
+            .chain(hotkey.map(|hk| (hk, action.clone())))
+            .collect();
+        self.hotkey_service.rebind_changed(previous);
         self.save();
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use futures::channel::mpsc::unbounded;
+
+    use super::*;
+    use crate::os::System;
+
+    fn config_service() -> ConfigService {
+        let config = Arc::new(RwLock::new(Config::default()));
+        let (hotkey_sender, _) = unbounded();
+        ConfigService::new(config, hotkey_sender, HotkeyBackend::Daemon)
+    }
+
+    #[test]
+    fn failed_reload_keeps_file() {
+        // Arrange
+        let dir = tempfile::tempdir().unwrap();
+        System::set_config_dir(dir.path().to_path_buf());
+        let mut config_service = config_service();
+        config_service.add_group("Browsers".to_string());
+        let broken = "groups: [\n";
+        std::fs::write(Config::path(), broken).unwrap();
+
+        // Act
+        let reloaded = config_service.reload().is_ok();
+        config_service.add_group("Editors".to_string());
+
+        // Assert
+        assert!(!reloaded);
+        assert!(config_service.load_error().is_some());
+        assert_eq!(std::fs::read_to_string(Config::path()).unwrap(), broken);
+    }
+
+    #[test]
+    fn failed_save_is_reported() {
+        // Arrange
+        let dir = tempfile::tempdir().unwrap();
+        System::set_config_dir(dir.path().to_path_buf());
+        let mut config_service = config_service();
+        std::fs::create_dir_all(Config::path()).unwrap(); // can't be replaced by a file
+
+        // Act
+        config_service.add_group("Browsers".to_string());
+
+        // Assert
+        assert!(config_service.save_error().is_some());
+        assert_eq!(config_service.config().groups().len(), 1);
+    }
+
+    #[test]
+    fn set_reverse_hotkey_of_unknown_group() {
+        // Arrange
+        let dir = tempfile::tempdir().unwrap();
+        System::set_config_dir(dir.path().to_path_buf());
+        let mut config_service = config_service();
+        let group_id = Uuid::new_v4();
+
+        // Act
+        let result = config_service.set_reverse_hotkey(group_id, ReverseHotkey::Disabled);
+
+        // Assert
+        assert_eq!(result, Err(HotkeyBindError::UnknownGroup { group_id }));
+    }
+}
@@ -0,0 +1,34 @@
+use dioxus::hooks::UnboundedSender;
+use log::{error, warn};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::models::Config;
+
+/// Notifies about changes to the config file until dropped
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl ConfigWatcher {
+    pub fn new(changed_sender: UnboundedSender<()>) -> notify::Result<Self> {
+        let path = Config::path();
+        let watched = path.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+                Ok(event) if is_change(&event) && event.paths.contains(&watched) => {
+                    let _ = changed_sender.unbounded_send(());
+                }
+                Ok(_) => {}
+                Err(e) => warn!("error watching config: {e}"),
+            })?;
+        // Editors often replace the file instead of writing to it, so we watch the directory
+        let dir = path.parent().expect("config file has a parent directory");
+        std::fs::create_dir_all(dir).inspect_err(|e| error!("could not create config dir: {e}"))?;
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+fn is_change(event: &Event) -> bool {
+    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+}
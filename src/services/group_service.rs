@@ -1,29 +1,48 @@
 use std::collections::VecDeque;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
+use std::time::{Duration, Instant};
 
+use anyhow::{Context, bail};
 use log::error;
 use uuid::Uuid;
 
-use crate::models::Identifiable;
-use crate::os::{App, AppObserver, AppQuery, Openable, System};
-use crate::services::ConfigReader;
+use crate::models::{CycleOrder, Direction, Identifiable, RepeatPolicy};
+use crate::os::{App, AppObserver, AppQuery, Hideable, Openable, Quittable, System};
+use crate::services::{Clock, ConfigReader, SystemClock};
 
 const MAX_HISTORY: usize = 1024; // Prevent potential memory leak
+const CYCLE_SESSION_TIMEOUT: Duration = Duration::from_secs(1);
 
 #[derive(Clone)]
 pub struct GroupService {
     config_reader: ConfigReader,
     history: Arc<RwLock<VecDeque<String>>>,
+    session: Arc<Mutex<Option<CycleSession>>>,
+    clock: Arc<dyn Clock>,
+}
+
+/// Quick successive presses walk a snapshot of the history, like holding Cmd-Tab
+struct CycleSession {
+    group_id: Uuid,
+    apps: Vec<App>,
+    position: usize,
+    last_press: Instant,
 }
 
 impl GroupService {
     pub fn new(config_reader: ConfigReader) -> Self {
+        Self::with_clock(config_reader, Arc::new(SystemClock))
+    }
+
+    fn with_clock(config_reader: ConfigReader, clock: Arc<dyn Clock>) -> Self {
         let history = Arc::new(RwLock::new(VecDeque::new()));
         Self::spawn_history_writer(history.clone());
         Self {
             config_reader,
             history,
+            session: Arc::new(Mutex::new(None)),
+            clock,
         }
     }
 
@@ -39,24 +58,184 @@ impl GroupService {
         });
     }
 
-    pub async fn open(&self, group_id: Uuid) {
-        let group = self.config_reader.read().group(group_id).unwrap().clone();
-        let apps = group.apps();
-        if let Some(app) = self
-            .next_app(apps)
-            .or_else(|| group.target.clone())
-            .or_else(|| self.find_in_history(apps)) // most recent
+    pub async fn open(&self, group_id: Uuid, direction: Direction) -> anyhow::Result<()> {
+        let group = self.config_reader.read().group(group_id)?.clone();
+        let apps = self.resolve_apps(group_id)?;
+        let next = match group.cycle {
+            CycleOrder::Order => self.next_app(&apps, direction),
+            CycleOrder::Recent => self.next_recent_app(group_id, &apps, direction),
+        };
+        if let Some((_, true)) = next
+            && self.leave(group.on_repeat, &apps).await?
+        {
+            return Ok(());
+        }
+        if let Some(app) = next
+            .map(|(app, _)| app)
+            .or_else(|| group.target())
+            .or_else(|| self.find_in_history(&apps)) // most recent
             .or_else(|| apps.first().cloned())
         {
-            Self::open_app(&app).await;
+            Self::open_app(&app).await?;
+        }
+        Ok(())
+    }
+
+    /// Skips cycling, unless the group has no target
+    pub async fn open_target(&self, group_id: Uuid) -> anyhow::Result<()> {
+        let target = self.config_reader.read().group(group_id)?.target();
+        match target {
+            Some(app) => Self::open_app(&app).await,
+            None => self.open(group_id, Direction::Forward).await,
+        }
+    }
+
+    /// Opens every app of the group that isn't running yet
+    pub async fn launch_all(&self, group_id: Uuid) -> anyhow::Result<()> {
+        let apps = self.config_reader.read().group(group_id)?.apps();
+        let stopped: Vec<App> = apps
+            .into_iter()
+            .filter(|app| !app.is_running().unwrap_or(false)) // rather open twice than not at all
+            .collect();
+        for_each_app(&stopped, "launch", Self::open_app).await
+    }
+
+    /// Also quits the running apps matched by rules
+    pub async fn quit_all(&self, group_id: Uuid) -> anyhow::Result<()> {
+        let apps = self.resolve_apps(group_id)?;
+        for_each_app(&apps, "quit", async |app: &App| {
+            app.quit()
+                .await
+                .with_context(|| format!("could not quit app '{app}'"))
+        })
+        .await
+    }
+
+    /// Apps of the group, including those its rules match among the running
+    /// apps and the history
+    fn resolve_apps(&self, group_id: Uuid) -> anyhow::Result<Vec<App>> {
+        let config = self.config_reader.read();
+        let group = config.group(group_id)?;
+        if group.matchers().is_empty() {
+            return Ok(group.apps());
+        }
+        Ok(group.resolve_apps(&self.candidates()))
+    }
+
+    /// Each rule of the group with the apps it currently matches
+    pub fn rule_matches(&self, group_id: Uuid) -> anyhow::Result<Vec<(String, Vec<App>)>> {
+        let config = self.config_reader.read();
+        let matchers = config.group(group_id)?.matchers();
+        if matchers.is_empty() {
+            return Ok(Vec::new());
+        }
+        let candidates = self.candidates();
+        Ok(matchers
+            .into_iter()
+            .map(|matcher| {
+                let mut apps: Vec<App> = candidates
+                    .iter()
+                    .filter(|app| matcher.matches(app))
+                    .cloned()
+                    .collect();
+                apps.sort_by_key(|app| app.id());
+                (matcher.to_string(), apps)
+            })
+            .collect())
+    }
+
+    /// Running apps and apps from the history, which rules can match
+    fn candidates(&self) -> Vec<App> {
+        let mut ids = System::running_apps()
+            .inspect_err(|error| error!("could not list running apps: {error}"))
+            .unwrap_or_default();
+        ids.extend(self.history.read().unwrap().iter().cloned());
+        ids.sort();
+        ids.dedup();
+        ids.into_iter().map(App::from).collect()
+    }
+
+    /// Goes back to the app that was used before the current one
+    pub async fn open_previous(&self) -> anyhow::Result<()> {
+        let current = System::current_app().ok().flatten().map(App::from);
+        if let Some(app) = self.find_outside_history(current.as_slice()) {
+            Self::open_app(&app).await?;
         }
+        Ok(())
     }
 
-    fn next_app(&self, apps: &[App]) -> Option<App> {
+    /// Also returns whether moving forward wrapped around to the start
+    fn next_app(&self, apps: &[App], direction: Direction) -> Option<(App, bool)> {
         let current_id = System::current_app().ok()??;
         let pos = apps.iter().position(|app| app.id() == current_id)?;
-        let next_pos = (pos + 1) % apps.len();
-        Some(apps[next_pos].clone())
+        let next = step(pos, apps.len(), direction);
+        Some((apps[next].clone(), wraps(pos, next, direction)))
+    }
+
+    fn next_recent_app(
+        &self,
+        group_id: Uuid,
+        apps: &[App],
+        direction: Direction,
+    ) -> Option<(App, bool)> {
+        let current_id = System::current_app().ok()??;
+        let now = self.clock.now();
+        let mut session = self.session.lock().unwrap();
+        let continues = session.as_ref().is_some_and(|s| {
+            s.group_id == group_id
+                && now - s.last_press < CYCLE_SESSION_TIMEOUT
+                && s.apps[s.position].id() == current_id
+        });
+        if !continues {
+            *session = None;
+            let apps = self.in_recent_order(apps);
+            let position = apps.iter().position(|app| app.id() == current_id)?;
+            *session = Some(CycleSession {
+                group_id,
+                apps,
+                position,
+                last_press: now,
+            });
+        }
+        let session = session.as_mut()?;
+        let previous = session.position;
+        session.position = step(previous, session.apps.len(), direction);
+        session.last_press = now;
+        let wrapped = wraps(previous, session.position, direction);
+        Some((session.apps[session.position].clone(), wrapped))
+    }
+
+    /// Applies the repeat policy of the group, returning false if it should cycle instead
+    async fn leave(&self, on_repeat: RepeatPolicy, apps: &[App]) -> anyhow::Result<bool> {
+        match on_repeat {
+            RepeatPolicy::Cycle => Ok(false),
+            RepeatPolicy::ToggleBack => {
+                let Some(app) = self.find_outside_history(apps) else {
+                    return Ok(false); // nowhere to go back to
+                };
+                Self::open_app(&app).await?;
+                Ok(true)
+            }
+            RepeatPolicy::Hide => {
+                for app in apps {
+                    if let Err(error) = app.hide().await {
+                        error!("Could not hide app '{app}' due to the following error: {error}");
+                    }
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    /// Apps that were never active keep their config order at the end
+    fn in_recent_order(&self, apps: &[App]) -> Vec<App> {
+        let history = self.history.read().unwrap();
+        let mut apps = apps.to_vec();
+        apps.sort_by_key(|app| {
+            let position = history.iter().position(|id| *id == app.id());
+            position.unwrap_or(usize::MAX)
+        });
+        apps
     }
 
     fn find_in_history(&self, apps: &[App]) -> Option<App> {
@@ -68,14 +247,441 @@ impl GroupService {
             .cloned()
     }
 
-    async fn open_app(app: &App) {
-        let result = app.open().await;
-        if let Err(error) = result {
-            // This can fail because the app was uninstalled, etc
-            error!(
-                "Could not open app '{}' due to the following error: {}",
-                app, error
-            );
+    /// The most recent app that isn't part of the group
+    fn find_outside_history(&self, apps: &[App]) -> Option<App> {
+        self.history
+            .read()
+            .unwrap()
+            .iter()
+            .find(|id| apps.iter().all(|a| a.id() != **id))
+            .cloned()
+            .map(App::from)
+    }
+
+    /// This can fail because the app was uninstalled, etc
+    pub(super) async fn open_app(app: &App) -> anyhow::Result<()> {
+        app.open()
+            .await
+            .with_context(|| format!("could not open app '{app}'"))
+    }
+}
+
+/// Tries every app, then fails with the errors of all apps that failed
+async fn for_each_app(
+    apps: &[App],
+    verb: &str,
+    f: impl AsyncFn(&App) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut errors = Vec::new();
+    for app in apps {
+        if let Err(error) = f(app).await {
+            errors.push(format!("{error:#}"));
+        }
+    }
+    if !errors.is_empty() {
+        bail!(
+            "could not {verb} {} of {} apps: {}",
+            errors.len(),
+            apps.len(),
+            errors.join("; ")
+        );
+    }
+    Ok(())
+}
+
+fn step(position: usize, len: usize, direction: Direction) -> usize {
+    match direction {
+        Direction::Forward => (position + 1) % len,
+        Direction::Backward => (position + len - 1) % len,
+    }
+}
+
+fn wraps(position: usize, next: usize, direction: Direction) -> bool {
+    direction == Direction::Forward && next <= position
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::models::{CURRENT_VERSION, Config};
+    use crate::services::clock::tests::ManualClock;
+
+    const SAFARI: &str = "com.apple.Safari";
+    const CHROME: &str = "com.google.Chrome";
+    const FIREFOX: &str = "org.mozilla.firefox";
+    const NOTES: &str = "com.apple.Notes";
+
+    fn setup(apps: &[&str], target: Option<&str>) -> (GroupService, Uuid) {
+        let (service, group_id, _) = setup_with(apps, target, CycleOrder::Order);
+        (service, group_id)
+    }
+
+    fn setup_with(
+        apps: &[&str],
+        target: Option<&str>,
+        cycle: CycleOrder,
+    ) -> (GroupService, Uuid, Arc<ManualClock>) {
+        setup_configured(apps, target, |config, group_id| {
+            config.set_cycle(group_id, cycle)
+        })
+    }
+
+    fn setup_on_repeat(apps: &[&str], on_repeat: RepeatPolicy) -> (GroupService, Uuid) {
+        let (service, group_id, _) = setup_configured(apps, None, |config, group_id| {
+            config.set_on_repeat(group_id, on_repeat)
+        });
+        (service, group_id)
+    }
+
+    fn setup_configured(
+        apps: &[&str],
+        target: Option<&str>,
+        configure: impl FnOnce(&mut Config, Uuid),
+    ) -> (GroupService, Uuid, Arc<ManualClock>) {
+        let mut config = Config::default();
+        let group_id = config.add_group("Browsers".to_string());
+        for app in apps {
+            System::install(app);
+            config.add_app(group_id, App::from(*app));
+        }
+        config.set_target(group_id, target.map(App::from));
+        configure(&mut config, group_id);
+        let config_reader = ConfigReader::new(Arc::new(RwLock::new(config)));
+        let clock = Arc::new(ManualClock::new());
+        let service = GroupService::with_clock(config_reader, clock.clone());
+        (service, group_id, clock)
+    }
+
+    /// Activates like the user would, then waits for the history writer
+    fn activate(service: &GroupService, app_id: &str) {
+        System::install(app_id);
+        System::activate(app_id);
+        wait_for_history(service, app_id);
+    }
+
+    fn press(service: &GroupService, group_id: Uuid) -> Option<String> {
+        press_in(service, group_id, Direction::Forward)
+    }
+
+    fn press_in(service: &GroupService, group_id: Uuid, direction: Direction) -> Option<String> {
+        let _ = block_on(service.open(group_id, direction)); // failures keep the frontmost app
+        let frontmost = System::frontmost();
+        if let Some(app_id) = &frontmost {
+            wait_for_history(service, app_id);
         }
+        frontmost
+    }
+
+    fn wait_for_history(service: &GroupService, app_id: &str) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while service.history.read().unwrap().front().map(String::as_str) != Some(app_id) {
+            assert!(Instant::now() < deadline, "history never reached {app_id}");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn press_three_times_with_safari_frontmost() {
+        // Arrange
+        let (service, group_id) = setup(&[SAFARI, CHROME, FIREFOX], None);
+        activate(&service, SAFARI);
+
+        // Act
+        let presses: Vec<_> = (0..3).map(|_| press(&service, group_id)).collect();
+
+        // Assert
+        let expected = [CHROME, FIREFOX, SAFARI].map(|id| Some(id.to_string()));
+        assert_eq!(presses, expected);
+    }
+
+    #[test]
+    fn open_target_from_outside_group() {
+        // Arrange
+        let (service, group_id) = setup(&[SAFARI, CHROME, FIREFOX], Some(FIREFOX));
+        activate(&service, CHROME);
+        activate(&service, NOTES);
+
+        // Act
+        let frontmost = press(&service, group_id);
+
+        // Assert
+        assert_eq!(frontmost.as_deref(), Some(FIREFOX));
+    }
+
+    #[test]
+    fn open_most_recent_from_outside_group() {
+        // Arrange
+        let (service, group_id) = setup(&[SAFARI, CHROME, FIREFOX], None);
+        activate(&service, FIREFOX);
+        activate(&service, CHROME);
+        activate(&service, NOTES);
+
+        // Act
+        let frontmost = press(&service, group_id);
+
+        // Assert
+        assert_eq!(frontmost.as_deref(), Some(CHROME));
+    }
+
+    #[test]
+    fn open_first_without_history() {
+        // Arrange
+        let (service, group_id) = setup(&[SAFARI, CHROME, FIREFOX], None);
+
+        // Act
+        let frontmost = press(&service, group_id);
+
+        // Assert
+        assert_eq!(frontmost.as_deref(), Some(SAFARI));
+    }
+
+    #[test]
+    fn open_failure_keeps_frontmost_app() {
+        // Arrange
+        let (service, group_id) = setup(&[SAFARI, CHROME], None);
+        activate(&service, SAFARI);
+        System::fail_open(CHROME, "app was uninstalled");
+
+        // Act
+        let frontmost = press(&service, group_id);
+
+        // Assert
+        assert_eq!(frontmost.as_deref(), Some(SAFARI));
+        assert_eq!(System::opened(), vec![CHROME.to_string()]);
+    }
+
+    #[test]
+    fn empty_group_does_nothing() {
+        // Arrange
+        let (service, group_id) = setup(&[], None);
+        activate(&service, NOTES);
+
+        // Act
+        let frontmost = press(&service, group_id);
+
+        // Assert
+        assert_eq!(frontmost.as_deref(), Some(NOTES));
+        assert_eq!(System::opened(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn recent_returns_to_previous_app() {
+        // Arrange
+        let (service, group_id, clock) =
+            setup_with(&[SAFARI, CHROME, FIREFOX], None, CycleOrder::Recent);
+        for app_id in [FIREFOX, CHROME, SAFARI] {
+            activate(&service, app_id);
+        }
+
+        // Act
+        let presses: Vec<_> = (0..2)
+            .map(|_| {
+                clock.advance(CYCLE_SESSION_TIMEOUT);
+                press(&service, group_id)
+            })
+            .collect();
+
+        // Assert
+        let expected = [CHROME, SAFARI].map(|id| Some(id.to_string()));
+        assert_eq!(presses, expected);
+    }
+
+    #[test]
+    fn recent_quick_presses_walk_history() {
+        // Arrange
+        let (service, group_id, _clock) =
+            setup_with(&[SAFARI, CHROME, FIREFOX], None, CycleOrder::Recent);
+        for app_id in [FIREFOX, CHROME, SAFARI] {
+            activate(&service, app_id);
+        }
+
+        // Act
+        let presses: Vec<_> = (0..3).map(|_| press(&service, group_id)).collect();
+
+        // Assert
+        let expected = [CHROME, FIREFOX, SAFARI].map(|id| Some(id.to_string()));
+        assert_eq!(presses, expected);
+    }
+
+    #[test]
+    fn recent_puts_unused_apps_last() {
+        // Arrange
+        let (service, group_id, _clock) =
+            setup_with(&[SAFARI, CHROME, FIREFOX], None, CycleOrder::Recent);
+        activate(&service, FIREFOX);
+
+        // Act
+        let presses: Vec<_> = (0..2).map(|_| press(&service, group_id)).collect();
+
+        // Assert
+        let expected = [SAFARI, CHROME].map(|id| Some(id.to_string()));
+        assert_eq!(presses, expected);
+    }
+
+    #[test]
+    fn press_backwards_with_safari_frontmost() {
+        // Arrange
+        let (service, group_id) = setup(&[SAFARI, CHROME, FIREFOX], None);
+        activate(&service, SAFARI);
+
+        // Act
+        let presses: Vec<_> = (0..3)
+            .map(|_| press_in(&service, group_id, Direction::Backward))
+            .collect();
+
+        // Assert
+        let expected = [FIREFOX, CHROME, SAFARI].map(|id| Some(id.to_string()));
+        assert_eq!(presses, expected);
+    }
+
+    #[test]
+    fn recent_backwards_undoes_overshoot() {
+        // Arrange
+        let (service, group_id, _clock) =
+            setup_with(&[SAFARI, CHROME, FIREFOX], None, CycleOrder::Recent);
+        for app_id in [FIREFOX, CHROME, SAFARI] {
+            activate(&service, app_id);
+        }
+
+        // Act
+        let forward: Vec<_> = (0..2).map(|_| press(&service, group_id)).collect();
+        let backward = press_in(&service, group_id, Direction::Backward);
+
+        // Assert
+        let expected = [CHROME, FIREFOX].map(|id| Some(id.to_string()));
+        assert_eq!(forward, expected);
+        assert_eq!(backward.as_deref(), Some(CHROME));
+    }
+
+    #[test]
+    fn toggle_back_after_last_app() {
+        // Arrange
+        let (service, group_id) = setup_on_repeat(&[SAFARI, CHROME], RepeatPolicy::ToggleBack);
+        activate(&service, NOTES);
+
+        // Act
+        let presses: Vec<_> = (0..3).map(|_| press(&service, group_id)).collect();
+
+        // Assert
+        let expected = [SAFARI, CHROME, NOTES].map(|id| Some(id.to_string()));
+        assert_eq!(presses, expected);
+    }
+
+    #[test]
+    fn toggle_back_without_history_cycles() {
+        // Arrange
+        let (service, group_id) = setup_on_repeat(&[SAFARI], RepeatPolicy::ToggleBack);
+        activate(&service, SAFARI);
+
+        // Act
+        let frontmost = press(&service, group_id);
+
+        // Assert
+        assert_eq!(frontmost.as_deref(), Some(SAFARI));
+    }
+
+    #[test]
+    fn hide_after_last_app() {
+        // Arrange
+        let (service, group_id) = setup_on_repeat(&[SAFARI, CHROME], RepeatPolicy::Hide);
+        activate(&service, SAFARI);
+        activate(&service, CHROME);
+
+        // Act
+        let frontmost = press(&service, group_id);
+
+        // Assert
+        assert_eq!(frontmost, None);
+        assert_eq!(
+            System::hidden(),
+            vec![SAFARI.to_string(), CHROME.to_string()]
+        );
+    }
+
+    #[test]
+    fn open_previous_app() {
+        // Arrange
+        let (service, _) = setup(&[], None);
+        activate(&service, NOTES);
+        activate(&service, SAFARI);
+
+        // Act
+        block_on(service.open_previous()).unwrap();
+
+        // Assert
+        assert_eq!(System::frontmost().as_deref(), Some(NOTES));
+    }
+
+    #[test]
+    fn launch_all_continues_after_error() {
+        // Arrange
+        let missing = "com.example.Missing";
+        let (service, group_id, _) = setup_configured(&[SAFARI, CHROME], None, |config, id| {
+            config.add_app(id, App::from(missing))
+        });
+
+        // Act
+        let result = block_on(service.launch_all(group_id));
+
+        // Assert
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains(missing), "{error}");
+        assert_eq!(System::opened(), vec![SAFARI, CHROME, missing]);
+    }
+
+    #[test]
+    fn launch_all_skips_running_apps() {
+        // Arrange
+        let (service, group_id) = setup(&[SAFARI, CHROME, FIREFOX], None);
+        activate(&service, CHROME);
+
+        // Act
+        let result = block_on(service.launch_all(group_id));
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(System::opened(), vec![SAFARI, FIREFOX]);
+    }
+
+    #[test]
+    fn quit_all_running_apps() {
+        // Arrange
+        let (service, group_id) = setup(&[SAFARI, CHROME, FIREFOX], None);
+        activate(&service, SAFARI);
+        activate(&service, FIREFOX);
+
+        // Act
+        let result = block_on(service.quit_all(group_id));
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(System::quit(), vec![SAFARI, FIREFOX]);
+        assert_eq!(System::frontmost(), None);
+    }
+
+    #[test]
+    fn press_cycles_through_apps_matched_by_rule() {
+        // Arrange
+        let (idea, goland) = ("com.jetbrains.intellij", "com.jetbrains.goland");
+        let content = format!(
+            "version: {CURRENT_VERSION}\ngroups:\n- name: IDEs\n  hotkey: null\n  target: null\n  apps:\n  - glob: com.jetbrains.*\n"
+        );
+        let config: Config = serde_yaml::from_str(&content).unwrap();
+        let group_id = config.groups()[0].id();
+        let config_reader = ConfigReader::new(Arc::new(RwLock::new(config)));
+        let service = GroupService::with_clock(config_reader, Arc::new(ManualClock::new()));
+        for app_id in [idea, goland, SAFARI] {
+            activate(&service, app_id);
+        }
+
+        // Act
+        let presses: Vec<_> = (0..2).map(|_| press(&service, group_id)).collect();
+
+        // Assert
+        assert_eq!(presses, [goland, idea].map(|id| Some(id.to_string())));
     }
 }
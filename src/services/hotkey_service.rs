@@ -1,45 +1,127 @@
 mod binder;
 mod error;
+mod gesture_detector;
+mod headless_binder;
+mod keystroke;
 
-use binder::{DioxusBinder, HotkeyBinder};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use binder::{DaemonBinder, DioxusBinder, HotkeyBinder};
 use dioxus::hooks::UnboundedSender;
 pub use error::HotkeyBindError;
-use log::error;
+use headless_binder::HeadlessBinder;
+pub use headless_binder::start_event_loop;
+use log::{error, warn};
 
 use crate::models::{Action, Hotkey};
 use crate::services::config_reader::ConfigReader;
 
-pub struct HotkeyService<B: HotkeyBinder = DioxusBinder> {
+/// How long the follow-up keys of a chord stay bound after its leader was pressed
+pub const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Where hotkeys are registered, chosen at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyBackend {
+    Window,   // through the Dioxus window, only while it exists
+    Headless, // on a thread of their own, see `start_event_loop`
+    Daemon,   // by the running daemon, while this process only edits the config
+}
+
+pub struct HotkeyService<B: HotkeyBinder = Box<dyn HotkeyBinder>> {
     binder: B,
     config_reader: ConfigReader,
+    leaders: HashMap<Hotkey, usize>, // number of bound chords per leader
+    pending_chord: Option<PendingChord>,
+    chords_started: u64,
+}
+
+/// Follow-up keys that are bound while a chord is pending
+struct PendingChord {
+    id: u64,
+    follow_ups: Vec<Hotkey>,
 }
 
-impl HotkeyService<DioxusBinder> {
+impl HotkeyService {
     pub fn new(
         config_reader: ConfigReader,
         hotkey_sender: UnboundedSender<(Hotkey, Action)>,
+        backend: HotkeyBackend,
     ) -> Self {
-        let mut service = Self {
-            config_reader: config_reader.clone(),
-            binder: DioxusBinder::new(hotkey_sender),
+        let binder: Box<dyn HotkeyBinder> = match backend {
+            HotkeyBackend::Window => {
+                Box::new(DioxusBinder::new(hotkey_sender, config_reader.clone()))
+            }
+            HotkeyBackend::Headless => {
+                Box::new(HeadlessBinder::new(hotkey_sender, config_reader.clone()))
+            }
+            HotkeyBackend::Daemon => Box::new(DaemonBinder),
         };
-        for (hotkey, action) in config_reader.read().bindings() {
-            service
-                .binder
-                .bind_hotkey(hotkey, &action)
-                .unwrap_or_else(|e| error!("error restoring hotkey on startup: {e}"));
-        }
+        let mut service = Self::with_binder(config_reader, binder);
+        service.bind_all();
         service
     }
 }
 
 impl<B: HotkeyBinder> HotkeyService<B> {
-    fn find_conflict(&self, hotkey: Hotkey) -> Option<Action> {
-        self.config_reader
-            .read()
-            .bindings()
-            .into_iter()
-            .find_map(|(hk, action)| (hk == hotkey).then_some(action))
+    fn with_binder(config_reader: ConfigReader, binder: B) -> Self {
+        Self {
+            binder,
+            config_reader,
+            leaders: HashMap::new(),
+            pending_chord: None,
+            chords_started: 0,
+        }
+    }
+
+    /// Binds every hotkey of the current config, e.g. on startup
+    fn bind_all(&mut self) {
+        let bindings = self.config_reader.read().bindings();
+        for (hotkey, action) in bindings {
+            self.register(hotkey, &action)
+                .unwrap_or_else(|e| error!("error restoring hotkey: {e}"));
+        }
+    }
+
+    /// Must be called before the config is replaced
+    pub fn unbind_all(&mut self) {
+        self.end_chord();
+        let bindings = self.config_reader.read().bindings();
+        for (hotkey, _) in bindings {
+            self.unregister(hotkey);
+        }
+    }
+
+    /// Rebinds only what changed since `previous`, after the config was replaced
+    pub fn rebind_changed(&mut self, previous: Vec<(Hotkey, Action)>) {
+        self.end_chord();
+        let current = self.config_reader.read().bindings();
+        for (hotkey, _) in previous.iter().filter(|binding| !current.contains(binding)) {
+            self.unbind_hotkey(Some(*hotkey));
+        }
+        for (hotkey, action) in current.into_iter().filter(|b| !previous.contains(b)) {
+            self.bind_hotkey(Some(hotkey), None, action)
+                .unwrap_or_else(|e| error!("error rebinding hotkey: {e}"));
+        }
+    }
+
+    /// Fails if another action among `bindings` already uses the hotkey
+    pub fn check_conflict(
+        &self,
+        hotkey: Hotkey,
+        action: &Action,
+        bindings: &[(Hotkey, Action)],
+    ) -> Result<(), HotkeyBindError> {
+        match bindings
+            .iter()
+            .find(|(hk, other)| hk.conflicts_with(hotkey) && other != action)
+        {
+            Some((_, conflict)) => Err(HotkeyBindError::Conflict {
+                hotkey,
+                conflict: conflict.describe(&self.config_reader.read()),
+            }),
+            None => Ok(()),
+        }
     }
 
     pub fn bind_hotkey(
@@ -51,25 +133,103 @@ impl<B: HotkeyBinder> HotkeyService<B> {
         if hotkey == existing_hotkey {
             return Ok(());
         }
-        if let Some(hk) = hotkey
-            && let Some(conflict) = self.find_conflict(hk)
-        {
-            return Err(HotkeyBindError::Conflict {
-                hotkey: hk,
-                conflict: conflict.describe(&self.config_reader.read()),
-            });
+        if let Some(hk) = hotkey {
+            let bindings = self.config_reader.read().bindings();
+            self.check_conflict(hk, &action, &bindings)?;
         }
 
         self.unbind_hotkey(existing_hotkey);
         if let Some(hk) = hotkey {
-            self.binder.bind_hotkey(hk, &action)?
+            self.register(hk, &action)?
         }
         Ok(())
     }
 
     pub fn unbind_hotkey(&mut self, hotkey: Option<Hotkey>) {
         if let Some(hk) = hotkey {
-            self.binder.unbind_hotkey(hk);
+            self.unregister(hk);
+        }
+    }
+
+    /// Chords are bound through their leader, which all chords it leads share
+    fn register(&mut self, hotkey: Hotkey, action: &Action) -> Result<(), HotkeyBindError> {
+        if !hotkey.is_chord() {
+            return self.binder.bind_hotkey(hotkey, action);
+        }
+        let leader = hotkey.leader();
+        let count = self.leaders.get(&leader).copied().unwrap_or_default();
+        if count == 0 {
+            self.binder
+                .bind_hotkey(leader, &Action::StartChord { leader })?;
+        }
+        self.leaders.insert(leader, count + 1);
+        Ok(())
+    }
+
+    fn unregister(&mut self, hotkey: Hotkey) {
+        if !hotkey.is_chord() {
+            return self.binder.unbind_hotkey(hotkey);
+        }
+        let leader = hotkey.leader();
+        match self.leaders.get(&leader).copied() {
+            Some(1) => {
+                self.leaders.remove(&leader);
+                self.binder.unbind_hotkey(leader);
+            }
+            Some(count) => {
+                self.leaders.insert(leader, count - 1);
+            }
+            None => warn!("leader {leader:?} is not bound"),
+        }
+    }
+
+    /// Binds the follow-up keys of the chords led by `leader` until the chord
+    /// ends, returning its id for `expire_chord`. If one can't be bound, e.g.
+    /// as another app holds it, the chord ends right away.
+    pub fn start_chord(&mut self, leader: Hotkey) -> Result<u64, HotkeyBindError> {
+        self.end_chord();
+        let chords: Vec<_> = self
+            .config_reader
+            .read()
+            .bindings()
+            .into_iter()
+            .filter(|(hotkey, _)| hotkey.is_chord() && hotkey.leader() == leader)
+            .collect();
+        let mut follow_ups = Vec::new();
+        for (hotkey, action) in chords {
+            if let Err(error) = self.binder.bind_hotkey(hotkey, &action) {
+                for hotkey in follow_ups {
+                    self.binder.unbind_hotkey(hotkey);
+                }
+                return Err(error);
+            }
+            follow_ups.push(hotkey);
+        }
+        self.chords_started += 1;
+        self.pending_chord = Some(PendingChord {
+            id: self.chords_started,
+            follow_ups,
+        });
+        Ok(self.chords_started)
+    }
+
+    /// Unbinds the follow-up keys again, e.g. after one was pressed
+    pub fn end_chord(&mut self) {
+        if let Some(chord) = self.pending_chord.take() {
+            for hotkey in chord.follow_ups {
+                self.binder.unbind_hotkey(hotkey);
+            }
+        }
+    }
+
+    /// Ends the chord after the timeout, unless another one was started since
+    pub fn expire_chord(&mut self, id: u64) {
+        if self
+            .pending_chord
+            .as_ref()
+            .is_some_and(|chord| chord.id == id)
+        {
+            self.end_chord();
         }
     }
 }
@@ -84,18 +244,9 @@ mod tests {
     use super::binder::tests::MockBinder;
     use super::binder::tests::MockEvent::*;
     use super::*;
-    use crate::models::Config;
+    use crate::models::{Config, Identifiable};
     use crate::services::hotkey_service::binder::tests::MockEvent;
 
-    impl HotkeyService<MockBinder> {
-        fn new_mock(config_reader: ConfigReader, binder: MockBinder) -> Self {
-            Self {
-                binder,
-                config_reader,
-            }
-        }
-    }
-
     fn setup_service() -> (
         Arc<RwLock<Config>>,
         HotkeyService<MockBinder>,
@@ -103,9 +254,12 @@ mod tests {
     ) {
         let config = Arc::new(RwLock::new(Config::default()));
         let (tx, rx) = std::sync::mpsc::channel();
-        let binder = MockBinder { event_sender: tx };
+        let binder = MockBinder {
+            event_sender: tx,
+            failing: None,
+        };
         let config_reader = ConfigReader::new(config.clone());
-        let service = HotkeyService::new_mock(config_reader, binder);
+        let service = HotkeyService::with_binder(config_reader, binder);
         (config, service, rx)
     }
 
@@ -215,4 +369,187 @@ mod tests {
         // Assert
         assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![Unregister(hotkey)]);
     }
+
+    #[test]
+    fn rebind_changed() {
+        // Arrange
+        let (config, mut service, rx) = setup_service();
+        let kept = Hotkey::new(Modifiers::META, Code::KeyK);
+        let old_hotkey = Hotkey::new(Modifiers::META, Code::KeyF);
+        let new_hotkey = Hotkey::new(Modifiers::META, Code::KeyG);
+        setup_group(config.clone(), "Kept", Some(kept));
+        let action = setup_group(config.clone(), "Changed", Some(old_hotkey));
+        let previous = config.read().unwrap().bindings();
+        let group_id = config.read().unwrap().groups()[1].id();
+        config
+            .write()
+            .unwrap()
+            .set_hotkey(group_id, Some(new_hotkey));
+
+        // Act
+        service.rebind_changed(previous);
+
+        // Assert
+        let reverse = config.read().unwrap().groups()[1].reverse_binding().1;
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![
+                Unregister(old_hotkey),
+                Unregister(old_hotkey.with_shift().unwrap()),
+                Register(new_hotkey, action),
+                Register(new_hotkey.with_shift().unwrap(), reverse),
+            ]
+        );
+    }
+
+    #[test]
+    fn bind_hotkey_conflict_with_derived_reverse() {
+        // Arrange
+        let (config, mut service, rx) = setup_service();
+        let hotkey = Hotkey::new(Modifiers::META, Code::KeyF);
+        setup_group(config.clone(), "Fst", Some(hotkey));
+        let new_action = setup_group(config, "Snd", None);
+        let shifted = hotkey.with_shift().unwrap();
+
+        // Act
+        let result = service.bind_hotkey(Some(shifted), None, new_action);
+
+        // Assert
+        assert_eq!(
+            result,
+            Err(HotkeyBindError::Conflict {
+                hotkey: shifted,
+                conflict: "cycle backwards through group 'Fst'".to_string()
+            })
+        );
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![]);
+    }
+
+    fn chord(follow_up: Code) -> Hotkey {
+        let leader = Hotkey::new(Modifiers::META | Modifiers::ALT, Code::KeyG);
+        leader.then(Hotkey::new(Modifiers::empty(), follow_up))
+    }
+
+    #[test]
+    fn bind_chords_share_leader() {
+        // Arrange
+        let (config, mut service, rx) = setup_service();
+        let (fst, snd) = (chord(Code::KeyW), chord(Code::KeyB));
+        setup_group(config.clone(), "Fst", Some(fst));
+        setup_group(config, "Snd", Some(snd));
+
+        // Act
+        service.bind_all();
+        service.unbind_hotkey(Some(fst));
+
+        // Assert
+        let leader = fst.leader();
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![Register(leader, Action::StartChord { leader })]
+        );
+    }
+
+    #[test]
+    fn start_chord_binds_follow_ups_until_expired() {
+        // Arrange
+        let (config, mut service, rx) = setup_service();
+        let hotkey = chord(Code::KeyW);
+        let action = setup_group(config.clone(), "Fst", Some(hotkey));
+        setup_group(
+            config.clone(),
+            "Snd",
+            Some(hotkey.leader().with_shift().unwrap()),
+        );
+
+        // Act
+        let id = service.start_chord(hotkey.leader()).unwrap();
+        service.expire_chord(id - 1);
+        let pending = rx.try_iter().collect::<Vec<_>>();
+        service.expire_chord(id);
+
+        // Assert
+        let reverse = config.read().unwrap().groups()[0].reverse_binding().1;
+        let shifted = hotkey.with_shift().unwrap();
+        assert_eq!(
+            pending,
+            vec![Register(hotkey, action), Register(shifted, reverse)]
+        );
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![Unregister(hotkey), Unregister(shifted)]
+        );
+    }
+
+    #[test]
+    fn bind_hotkey_conflict_with_chord_prefix() {
+        // Arrange
+        let (config, mut service, rx) = setup_service();
+        let hotkey = chord(Code::KeyW);
+        setup_group(config.clone(), "Fst", Some(hotkey));
+        let new_action = setup_group(config, "Snd", None);
+
+        // Act
+        let result = service.bind_hotkey(Some(hotkey.leader()), None, new_action);
+
+        // Assert
+        assert_eq!(
+            result,
+            Err(HotkeyBindError::Conflict {
+                hotkey: hotkey.leader(),
+                conflict: "open group 'Fst'".to_string()
+            })
+        );
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![]);
+    }
+
+    #[test]
+    fn bind_chord_conflict_with_follow_up() {
+        // Arrange
+        let (config, mut service, rx) = setup_service();
+        let hotkey = chord(Code::KeyW);
+        setup_group(config.clone(), "Fst", hotkey.follow_up());
+        let new_action = setup_group(config, "Snd", None);
+
+        // Act
+        let result = service.bind_hotkey(Some(hotkey), None, new_action);
+
+        // Assert
+        assert_eq!(
+            result,
+            Err(HotkeyBindError::Conflict {
+                hotkey,
+                conflict: "open group 'Fst'".to_string()
+            })
+        );
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![]);
+    }
+
+    #[test]
+    fn start_chord_fails_if_follow_up_is_taken() {
+        // Arrange
+        let (config, mut service, rx) = setup_service();
+        let (fst, snd) = (chord(Code::KeyW), chord(Code::KeyB));
+        let action = setup_group(config.clone(), "Fst", Some(fst));
+        setup_group(config.clone(), "Snd", Some(snd));
+        service.binder.failing = Some(snd);
+
+        // Act
+        let result = service.start_chord(fst.leader());
+
+        // Assert
+        assert_eq!(result, Err(HotkeyBindError::Unknown { hotkey: snd }));
+        assert!(service.pending_chord.is_none());
+        let reverse = config.read().unwrap().groups()[0].reverse_binding().1;
+        let shifted = fst.with_shift().unwrap();
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![
+                Register(fst, action),
+                Register(shifted, reverse),
+                Unregister(fst),
+                Unregister(shifted)
+            ]
+        );
+    }
 }
@@ -1,42 +1,80 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use dioxus::desktop::{ShortcutHandle, ShortcutRegistryError, window};
 use dioxus::hooks::UnboundedSender;
-use global_hotkey::HotKeyState::Pressed;
 use log::warn;
 
 use crate::models::{Action, Hotkey};
 use crate::services::hotkey_service::error::HotkeyBindError;
+use crate::services::hotkey_service::keystroke::Keystroke;
+use crate::services::{Clock, ConfigReader, SystemClock};
 
 pub trait HotkeyBinder {
     fn bind_hotkey(&mut self, hotkey: Hotkey, action: &Action) -> Result<(), HotkeyBindError>;
     fn unbind_hotkey(&mut self, hotkey: Hotkey);
 }
 
+impl<B: HotkeyBinder + ?Sized> HotkeyBinder for Box<B> {
+    fn bind_hotkey(&mut self, hotkey: Hotkey, action: &Action) -> Result<(), HotkeyBindError> {
+        (**self).bind_hotkey(hotkey, action)
+    }
+
+    fn unbind_hotkey(&mut self, hotkey: Hotkey) {
+        (**self).unbind_hotkey(hotkey)
+    }
+}
+
+/// Leaves hotkeys to the daemon, which follows the saved config
+pub struct DaemonBinder;
+
+impl HotkeyBinder for DaemonBinder {
+    fn bind_hotkey(&mut self, _hotkey: Hotkey, _action: &Action) -> Result<(), HotkeyBindError> {
+        Ok(())
+    }
+
+    fn unbind_hotkey(&mut self, _hotkey: Hotkey) {}
+}
+
+/// Registers each keystroke once, for all the gestures bound to it
 pub struct DioxusBinder {
     hotkey_sender: UnboundedSender<(Hotkey, Action)>,
-    handles: HashMap<Hotkey, ShortcutHandle>,
+    config_reader: ConfigReader,
+    clock: Arc<dyn Clock>,
+    shortcuts: HashMap<Hotkey, (ShortcutHandle, Arc<Keystroke>)>,
 }
 
 impl DioxusBinder {
-    pub(super) fn new(hotkey_sender: UnboundedSender<(Hotkey, Action)>) -> Self {
+    pub(super) fn new(
+        hotkey_sender: UnboundedSender<(Hotkey, Action)>,
+        config_reader: ConfigReader,
+    ) -> Self {
         Self {
             hotkey_sender,
-            handles: HashMap::new(),
+            config_reader,
+            clock: Arc::new(SystemClock),
+            shortcuts: HashMap::new(),
         }
     }
 }
 
 impl HotkeyBinder for DioxusBinder {
     fn bind_hotkey(&mut self, hotkey: Hotkey, action: &Action) -> Result<(), HotkeyBindError> {
-        let my_hotkey_sender = self.hotkey_sender.clone();
-        let my_action = action.clone();
-        let callback = move |state| {
-            if state == Pressed {
-                my_hotkey_sender
-                    .unbounded_send((hotkey, my_action.clone()))
-                    .unwrap();
-            }
+        let keystroke = hotkey.without_gesture();
+        if let Some((_, existing)) = self.shortcuts.get(&keystroke) {
+            existing.bind(hotkey.gesture(), action.clone());
+            return Ok(());
+        }
+        let my_keystroke = Arc::new(Keystroke::new(
+            hotkey,
+            action.clone(),
+            self.clock.clone(),
+            self.hotkey_sender.clone(),
+            self.config_reader.clone(),
+        ));
+        let callback = {
+            let my_keystroke = my_keystroke.clone();
+            move |state| my_keystroke.clone().handle(state)
         };
         let handle = window()
             .create_shortcut(hotkey.global_hotkey(), callback)
@@ -44,15 +82,20 @@ impl HotkeyBinder for DioxusBinder {
                 ShortcutRegistryError::InvalidShortcut(_) => HotkeyBindError::Invalid { hotkey },
                 _ => HotkeyBindError::Unknown { hotkey },
             })?;
-        self.handles.insert(hotkey, handle);
+        self.shortcuts.insert(keystroke, (handle, my_keystroke));
         Ok(())
     }
 
     fn unbind_hotkey(&mut self, hotkey: Hotkey) {
-        if let Some(handle) = self.handles.remove(&hotkey) {
-            window().remove_shortcut(handle);
-        } else {
+        let keystroke = hotkey.without_gesture();
+        let Some((_, existing)) = self.shortcuts.get(&keystroke) else {
             warn!("missing handle for hotkey {:?}", hotkey);
+            return;
+        };
+        if existing.unbind(hotkey.gesture())
+            && let Some((handle, _)) = self.shortcuts.remove(&keystroke)
+        {
+            window().remove_shortcut(handle);
         }
     }
 }
@@ -71,10 +114,14 @@ pub mod tests {
 
     pub struct MockBinder {
         pub event_sender: Sender<MockEvent>,
+        pub failing: Option<Hotkey>, // as if held by another app
     }
 
     impl HotkeyBinder for MockBinder {
         fn bind_hotkey(&mut self, hotkey: Hotkey, action: &Action) -> Result<(), HotkeyBindError> {
+            if self.failing == Some(hotkey) {
+                return Err(HotkeyBindError::Unknown { hotkey });
+            }
             self.event_sender
                 .send(MockEvent::Register(hotkey, action.clone()))
                 .unwrap();
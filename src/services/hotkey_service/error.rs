@@ -1,8 +1,11 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use uuid::Uuid;
 
 use crate::models::Hotkey;
 
-#[derive(Error, Debug, Clone, PartialEq)]
+#[derive(Error, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")] // as reported over IPC
 pub enum HotkeyBindError {
     #[error("{hotkey} is already bound to {conflict}")]
     Conflict { hotkey: Hotkey, conflict: String },
@@ -12,4 +15,7 @@ pub enum HotkeyBindError {
 
     #[error("{hotkey} could not be registered")]
     Unknown { hotkey: Hotkey },
+
+    #[error("there is no group with id {group_id}")] // e.g. removed meanwhile
+    UnknownGroup { group_id: Uuid },
 }
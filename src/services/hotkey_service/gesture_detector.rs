@@ -0,0 +1,191 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::models::{Gesture, GestureTiming};
+use crate::services::Clock;
+
+/// Tells the gestures of one keystroke apart from its presses and releases
+pub struct GestureDetector {
+    clock: Arc<dyn Clock>,
+    state: State,
+}
+
+enum State {
+    Idle,
+    Down(Instant),    // pressed at
+    Waiting(Instant), // released at, a second press may follow
+    SecondDown,       // the double press was already reported
+}
+
+impl GestureDetector {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            state: State::Idle,
+        }
+    }
+
+    /// Reports right away if a single press is all that's `bound`, otherwise
+    /// decides on release or double press
+    pub fn press(&mut self, bound: &[Gesture], timing: GestureTiming) -> Option<Gesture> {
+        let now = self.clock.now();
+        match self.state {
+            State::Down(_) | State::SecondDown => None, // held down, the key repeats
+            State::Waiting(released) if now - released <= timing.double_press() => {
+                self.state = State::SecondDown;
+                Some(Gesture::DoublePress)
+            }
+            State::Waiting(_) => {
+                self.state = State::Down(now);
+                Some(Gesture::Press) // lapsed before `expire` came around
+            }
+            State::Idle if bound.iter().all(Gesture::is_press) => Some(Gesture::Press),
+            State::Idle => {
+                self.state = State::Down(now);
+                None
+            }
+        }
+    }
+
+    pub fn release(&mut self, bound: &[Gesture], timing: GestureTiming) -> Option<Gesture> {
+        let now = self.clock.now();
+        match self.state {
+            State::Down(pressed) => {
+                if bound.contains(&Gesture::Hold) && now - pressed >= timing.hold() {
+                    self.state = State::Idle;
+                    Some(Gesture::Hold)
+                } else if bound.contains(&Gesture::DoublePress) {
+                    self.state = State::Waiting(now);
+                    None
+                } else {
+                    self.state = State::Idle;
+                    Some(Gesture::Press)
+                }
+            }
+            State::SecondDown => {
+                self.state = State::Idle;
+                None
+            }
+            State::Idle | State::Waiting(_) => None,
+        }
+    }
+
+    /// Reports a single press once no second press followed in time
+    pub fn expire(&mut self, timing: GestureTiming) -> Option<Gesture> {
+        match self.state {
+            State::Waiting(released) if self.clock.now() - released >= timing.double_press() => {
+                self.state = State::Idle;
+                Some(Gesture::Press)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        matches!(self.state, State::Waiting(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::services::clock::tests::ManualClock;
+
+    const ALL: [Gesture; 3] = [Gesture::Press, Gesture::DoublePress, Gesture::Hold];
+
+    fn setup() -> (GestureDetector, Arc<ManualClock>, GestureTiming) {
+        let clock = Arc::new(ManualClock::new());
+        let detector = GestureDetector::new(clock.clone());
+        (detector, clock, GestureTiming::default())
+    }
+
+    fn millis(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn press_alone_is_reported_immediately() {
+        // Arrange
+        let (mut detector, _clock, timing) = setup();
+
+        // Act
+        let pressed = detector.press(&[Gesture::Press], timing);
+        let released = detector.release(&[Gesture::Press], timing);
+
+        // Assert
+        assert_eq!(pressed, Some(Gesture::Press));
+        assert_eq!(released, None);
+    }
+
+    #[test]
+    fn press_is_reported_after_double_press_window() {
+        // Arrange
+        let (mut detector, clock, timing) = setup();
+
+        // Act
+        detector.press(&ALL, timing);
+        clock.advance(millis(50));
+        let released = detector.release(&ALL, timing);
+        clock.advance(millis(100));
+        let early = detector.expire(timing);
+        clock.advance(millis(200));
+        let expired = detector.expire(timing);
+
+        // Assert
+        assert_eq!((released, early), (None, None));
+        assert_eq!(expired, Some(Gesture::Press));
+        assert!(!detector.is_waiting());
+    }
+
+    #[test]
+    fn double_press_within_window() {
+        // Arrange
+        let (mut detector, clock, timing) = setup();
+
+        // Act
+        detector.press(&ALL, timing);
+        detector.release(&ALL, timing);
+        clock.advance(millis(200));
+        let second = detector.press(&ALL, timing);
+        let released = detector.release(&ALL, timing);
+
+        // Assert
+        assert_eq!(second, Some(Gesture::DoublePress));
+        assert_eq!((released, detector.expire(timing)), (None, None));
+    }
+
+    #[test]
+    fn hold_ignores_key_repeats() {
+        // Arrange
+        let (mut detector, clock, timing) = setup();
+
+        // Act
+        detector.press(&ALL, timing);
+        clock.advance(millis(300));
+        let repeated = detector.press(&ALL, timing);
+        clock.advance(millis(300));
+        let released = detector.release(&ALL, timing);
+
+        // Assert
+        assert_eq!(repeated, None);
+        assert_eq!(released, Some(Gesture::Hold));
+    }
+
+    #[test]
+    fn long_press_without_hold_is_a_press() {
+        // Arrange
+        let bound = [Gesture::Press, Gesture::Hold];
+        let (mut detector, clock, timing) = setup();
+
+        // Act
+        detector.press(&bound, timing);
+        clock.advance(millis(100));
+        let released = detector.release(&bound, timing);
+
+        // Assert
+        assert_eq!(released, Some(Gesture::Press));
+        assert!(!detector.is_waiting());
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, Once, mpsc};
+use std::thread;
+
+use dioxus::hooks::UnboundedSender;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager};
+use log::{debug, warn};
+
+use crate::models::{Action, Hotkey};
+use crate::services::hotkey_service::binder::HotkeyBinder;
+use crate::services::hotkey_service::error::HotkeyBindError;
+use crate::services::hotkey_service::keystroke::Keystroke;
+use crate::services::{Clock, ConfigReader, SystemClock};
+
+/// Keystrokes by the id of their registered hotkey, looked up by the event loop
+static ROUTES: LazyLock<Mutex<HashMap<u32, Arc<Keystroke>>>> = LazyLock::new(Default::default);
+static EVENT_LOOP: Once = Once::new();
+
+/// Handles hotkey events on a thread of their own rather than in a window's
+/// event loop. Must be started before the Dioxus runtime claims the events.
+pub fn start_event_loop() {
+    EVENT_LOOP.call_once(|| {
+        let (event_sender, event_receiver) = mpsc::channel::<GlobalHotKeyEvent>();
+        GlobalHotKeyEvent::set_event_handler(Some(move |event| {
+            let _ = event_sender.send(event);
+        }));
+        thread::Builder::new()
+            .name("hotkey-events".to_string())
+            .spawn(move || {
+                for event in event_receiver {
+                    let keystroke = ROUTES.lock().unwrap().get(&event.id).cloned();
+                    match keystroke {
+                        Some(keystroke) => keystroke.handle(event.state),
+                        None => debug!("ignoring event of unknown hotkey {}", event.id),
+                    }
+                }
+            })
+            .expect("could not spawn hotkey event loop");
+    });
+}
+
+/// Registers keystrokes with a `GlobalHotKeyManager` of its own, so hotkeys
+/// work without any window
+pub struct HeadlessBinder {
+    manager: GlobalHotKeyManager,
+    hotkey_sender: UnboundedSender<(Hotkey, Action)>,
+    config_reader: ConfigReader,
+    clock: Arc<dyn Clock>,
+    keystrokes: HashMap<Hotkey, Arc<Keystroke>>,
+}
+
+impl HeadlessBinder {
+    /// Like the manager, this must be created on a thread that runs an event
+    /// loop, which on macOS is the main thread
+    pub(super) fn new(
+        hotkey_sender: UnboundedSender<(Hotkey, Action)>,
+        config_reader: ConfigReader,
+    ) -> Self {
+        start_event_loop();
+        Self {
+            manager: GlobalHotKeyManager::new().expect("could not create hotkey manager"),
+            hotkey_sender,
+            config_reader,
+            clock: Arc::new(SystemClock),
+            keystrokes: HashMap::new(),
+        }
+    }
+}
+
+impl HotkeyBinder for HeadlessBinder {
+    fn bind_hotkey(&mut self, hotkey: Hotkey, action: &Action) -> Result<(), HotkeyBindError> {
+        let keystroke = hotkey.without_gesture();
+        if let Some(existing) = self.keystrokes.get(&keystroke) {
+            existing.bind(hotkey.gesture(), action.clone());
+            return Ok(());
+        }
+        let global_hotkey = hotkey.global_hotkey();
+        self.manager.register(global_hotkey).map_err(|e| match e {
+            global_hotkey::Error::HotKeyParseError(_)
+            | global_hotkey::Error::UnrecognizedHotKeyCode(_) => {
+                HotkeyBindError::Invalid { hotkey }
+            }
+            e => {
+                warn!("could not register hotkey {hotkey:?}: {e}");
+                HotkeyBindError::Unknown { hotkey }
+            }
+        })?;
+        let my_keystroke = Arc::new(Keystroke::new(
+            hotkey,
+            action.clone(),
+            self.clock.clone(),
+            self.hotkey_sender.clone(),
+            self.config_reader.clone(),
+        ));
+        ROUTES
+            .lock()
+            .unwrap()
+            .insert(global_hotkey.id(), my_keystroke.clone());
+        self.keystrokes.insert(keystroke, my_keystroke);
+        Ok(())
+    }
+
+    fn unbind_hotkey(&mut self, hotkey: Hotkey) {
+        let keystroke = hotkey.without_gesture();
+        let Some(existing) = self.keystrokes.get(&keystroke) else {
+            warn!("missing keystroke for hotkey {:?}", hotkey);
+            return;
+        };
+        if existing.unbind(hotkey.gesture()) {
+            self.keystrokes.remove(&keystroke);
+            let global_hotkey = keystroke.global_hotkey();
+            ROUTES.lock().unwrap().remove(&global_hotkey.id());
+            self.manager
+                .unregister(global_hotkey)
+                .unwrap_or_else(|e| warn!("could not unregister hotkey {hotkey:?}: {e}"));
+        }
+    }
+}
+
+impl Drop for HeadlessBinder {
+    fn drop(&mut self) {
+        let mut routes = ROUTES.lock().unwrap();
+        for keystroke in self.keystrokes.keys() {
+            routes.remove(&keystroke.global_hotkey().id());
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use dioxus::hooks::UnboundedSender;
+use global_hotkey::HotKeyState;
+
+use crate::models::{Action, Gesture, Hotkey};
+use crate::services::hotkey_service::gesture_detector::GestureDetector;
+use crate::services::{Clock, ConfigReader};
+
+/// The actions bound to the gestures of one keystroke
+pub struct Keystroke {
+    hotkey: Hotkey,
+    actions: Mutex<HashMap<Gesture, Action>>,
+    detector: Mutex<GestureDetector>,
+    hotkey_sender: UnboundedSender<(Hotkey, Action)>,
+    config_reader: ConfigReader,
+}
+
+impl Keystroke {
+    pub fn new(
+        hotkey: Hotkey,
+        action: Action,
+        clock: Arc<dyn Clock>,
+        hotkey_sender: UnboundedSender<(Hotkey, Action)>,
+        config_reader: ConfigReader,
+    ) -> Self {
+        Self {
+            hotkey: hotkey.without_gesture(),
+            actions: Mutex::new(HashMap::from([(hotkey.gesture(), action)])),
+            detector: Mutex::new(GestureDetector::new(clock)),
+            hotkey_sender,
+            config_reader,
+        }
+    }
+
+    pub fn bind(&self, gesture: Gesture, action: Action) {
+        self.actions.lock().unwrap().insert(gesture, action);
+    }
+
+    /// Whether no gestures are left, so the keystroke can be unregistered
+    pub fn unbind(&self, gesture: Gesture) -> bool {
+        let mut actions = self.actions.lock().unwrap();
+        actions.remove(&gesture);
+        actions.is_empty()
+    }
+
+    pub fn handle(self: Arc<Self>, state: HotKeyState) {
+        let timing = self.config_reader.read().gesture_timing();
+        let bound: Vec<_> = self.actions.lock().unwrap().keys().copied().collect();
+        let mut detector = self.detector.lock().unwrap();
+        let gesture = match state {
+            HotKeyState::Pressed => detector.press(&bound, timing),
+            HotKeyState::Released => detector.release(&bound, timing),
+        };
+        if detector.is_waiting() {
+            let my_keystroke = self.clone();
+            thread::spawn(move || {
+                thread::sleep(timing.double_press());
+                let gesture = my_keystroke.detector.lock().unwrap().expire(timing);
+                my_keystroke.send(gesture);
+            });
+        }
+        drop(detector);
+        self.send(gesture);
+    }
+
+    fn send(&self, gesture: Option<Gesture>) {
+        let Some(gesture) = gesture else { return };
+        if let Some(action) = self.actions.lock().unwrap().get(&gesture) {
+            let hotkey = self.hotkey.with_gesture(gesture);
+            self.hotkey_sender
+                .unbounded_send((hotkey, action.clone()))
+                .unwrap();
+        }
+    }
+}
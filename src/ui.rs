@@ -1,5 +1,10 @@
+mod app_actions;
 mod app_list;
+mod backup_list;
+mod config_error_banner;
+mod config_sync;
 mod group_config;
+mod ipc_listener;
 mod root;
 mod util;
 
@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use dioxus::desktop::window;
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{ToastOptions, consume_toast};
+use uuid::Uuid;
+
+use crate::models::{Action, Hotkey};
+use crate::services::{ActionService, CHORD_TIMEOUT, ConfigService};
+use crate::ui::config_sync::reload;
+use crate::ui::util::use_listener;
+use crate::util::sleep;
+
+/// Handles hotkeys that act on GroupCtrl itself, see `Action::targets_group_ctrl`,
+/// that complete a chord, or whose errors are reported, see `reports_errors`
+#[component]
+pub fn AppActions(
+    config_service: Signal<ConfigService>,
+    selected: Signal<HashSet<Uuid>>,
+) -> Element {
+    let mut app_actions = use_context::<Signal<Option<UnboundedSender<(Hotkey, Action)>>>>();
+    let action_service = use_context::<ActionService>();
+    let sender = use_listener(Callback::new(move |(hotkey, action): (Hotkey, Action)| {
+        if hotkey.is_chord() {
+            config_service.write().end_chord();
+        }
+        match action {
+            Action::StartChord { leader } => start_chord(config_service, leader),
+            Action::ShowGroupCtrlWindow => {
+                window().set_visible(true);
+                window().set_focus();
+            }
+            Action::ReloadConfig => reload(config_service, selected),
+            Action::SwitchProfile { profile_id } => {
+                config_service.write().switch_profile(profile_id);
+            }
+            action if reports_errors(&action) => execute_reporting(action_service.clone(), action),
+            action => {
+                let service = action_service.clone();
+                spawn(async move {
+                    service.execute(&action).await;
+                });
+            }
+        }
+    }));
+    use_effect(move || app_actions.set(Some(sender.clone())));
+
+    rsx! {}
+}
+
+/// Actions on all apps of a group, whose errors are gathered in one toast
+/// rather than only logged, whether run by hotkey or button. Without a window,
+/// the daemon only logs them, see `Daemon::execute`
+pub fn reports_errors(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::LaunchAllApps { .. } | Action::QuitAllApps { .. }
+    )
+}
+
+pub fn execute_reporting(action_service: ActionService, action: Action) {
+    let title = match action {
+        Action::LaunchAllApps { .. } => "Could not launch all apps",
+        Action::QuitAllApps { .. } => "Could not quit all apps",
+        _ => "Could not run action",
+    };
+    spawn(async move {
+        if let Err(error) = action_service.try_execute(&action).await {
+            consume_toast().error(
+                title.to_string(),
+                ToastOptions::new().description(format!("{error:#}")),
+            );
+        }
+    });
+}
+
+/// Keeps the follow-up keys bound until one is pressed or the chord times out
+fn start_chord(mut config_service: Signal<ConfigService>, leader: Hotkey) {
+    let result = config_service.write().start_chord(leader);
+    let id = match result {
+        Ok(id) => id,
+        Err(error) => {
+            consume_toast().error(
+                format!("Could not start chord {leader}"),
+                ToastOptions::new().description(error.to_string()),
+            );
+            return;
+        }
+    };
+    spawn(async move {
+        sleep(CHORD_TIMEOUT).await;
+        config_service.write().expire_chord(id);
+    });
+}
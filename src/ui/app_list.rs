@@ -2,12 +2,19 @@ use std::collections::HashSet;
 
 use dioxus::prelude::*;
 
+use crate::components::button::{Button, ButtonVariant};
 use crate::models::Identifiable;
 use crate::os::App;
 use crate::ui::util::{AppLabel, ListMenu, use_selection};
 
+/// `rules` are shown with the apps they match, which can't be removed one by one.
+/// Matching queries the OS, so it's only redone when asked to.
 #[component]
-pub fn AppList(apps: Vec<App>) -> Element {
+pub fn AppList(
+    apps: Vec<App>,
+    rules: Vec<(String, Vec<App>)>,
+    refresh_rules: Callback<()>,
+) -> Element {
     let selected = use_signal(HashSet::<String>::new);
     rsx! {
         div {
@@ -16,7 +23,18 @@ pub fn AppList(apps: Vec<App>) -> Element {
             div {
                 class: "flex items-center justify-between w-full !p-2 !pb-0",
                 label { r#for: "app-list", class: "pl-1.25", "Apps" }
-                ListMenu { selected }
+                div { class: "flex items-center gap-1",
+                    if !rules.is_empty() {
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            class: "button !h-6 !px-2 text-xs",
+                            title: "Match rules against the running and recent apps again",
+                            onclick: move |_| refresh_rules.call(()),
+                            "Refresh"
+                        }
+                    }
+                    ListMenu { selected }
+                }
             }
             div {
                 class: "sidebar-group-content flex-1 min-h-0 overflow-y-auto p-1",
@@ -32,12 +50,34 @@ pub fn AppList(apps: Vec<App>) -> Element {
                             AppRow { app, selected }
                         }
                     }
+                    for (rule, matched) in rules {
+                        li {
+                            class: "sidebar-menu-item",
+                            "data-sidebar": "menu-item",
+                            RuleRow { rule, matched }
+                        }
+                    }
                 }
             }
         }
     }
 }
 
+#[component]
+fn RuleRow(rule: String, matched: Vec<App>) -> Element {
+    rsx! {
+        div { class: "flex flex-col gap-1 p-2 text-sm",
+            span { class: "opacity-70", "{rule}" }
+            if matched.is_empty() {
+                span { class: "pl-4 opacity-50", "No running or recent apps match" }
+            }
+            for app in matched {
+                div { class: "pl-4", AppLabel { app } }
+            }
+        }
+    }
+}
+
 #[component]
 fn AppRow(app: App, selected: Signal<HashSet<String>>) -> Element {
     let (is_selected, toggle) = use_selection(app.id(), selected);
@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{ToastOptions, consume_toast};
+use uuid::Uuid;
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::sheet::*;
+use crate::models::{Backup, Config};
+use crate::services::ConfigService;
+use crate::ui::config_sync::retain_existing;
+
+#[component]
+pub fn BackupList(
+    config_service: Signal<ConfigService>,
+    selected: Signal<HashSet<Uuid>>,
+) -> Element {
+    let mut open = use_signal(|| false);
+    let mut backups = use_signal(Vec::<Backup>::new);
+    let show = move |_| {
+        match Config::backups() {
+            Ok(list) => backups.set(list),
+            Err(error) => consume_toast().error(
+                "Could not list backups".to_string(),
+                ToastOptions::new().description(error.to_string()),
+            ),
+        }
+        open.set(true);
+    };
+    let restore = move |backup: Backup| {
+        if let Err(error) = config_service.write().restore_backup(&backup) {
+            consume_toast().error(
+                "Could not restore backup".to_string(),
+                ToastOptions::new().description(error.to_string()),
+            );
+            return;
+        }
+        retain_existing(config_service, selected);
+        open.set(false);
+    };
+
+    rsx! {
+        Button { variant: ButtonVariant::Ghost, onclick: show, "Backups" }
+        Sheet {
+            open: open(),
+            on_open_change: move |value| open.set(value),
+            SheetContent { side: SheetSide::Right,
+                SheetHeader {
+                    SheetTitle { "Backups" }
+                    SheetDescription { "Roll back to a previously saved config." }
+                }
+                div { class: "flex flex-col gap-1 p-2 overflow-y-auto",
+                    if backups().is_empty() {
+                        span { class: "text-sm text-(--muted-text)", "No backups yet" }
+                    }
+                    for (created, backup) in backups().into_iter().map(|b| (created_label(&b), b)) {
+                        div {
+                            key: "{backup.path.display()}",
+                            class: "flex items-center justify-between gap-2 text-sm",
+                            span { "{created}" }
+                            Button {
+                                variant: ButtonVariant::Outline,
+                                onclick: move |_| restore(backup.clone()),
+                                "Restore"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn created_label(backup: &Backup) -> String {
+    backup.created.format("%Y-%m-%d %H:%M:%S").to_string()
+}
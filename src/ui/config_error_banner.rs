@@ -0,0 +1,50 @@
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{ToastOptions, consume_toast};
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::models::Config;
+use crate::services::ConfigService;
+
+#[component]
+pub fn ConfigErrorBanner(config_service: Signal<ConfigService>) -> Element {
+    let Some((error, quarantined)) = config_service
+        .read()
+        .load_error()
+        .map(|e| (e.to_string(), e.is_quarantined()))
+    else {
+        return rsx! {};
+    };
+    let quarantine_path = Config::quarantine_path().display().to_string();
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-2 m-2 p-2 rounded text-sm bg-(--secondary-error-color)",
+            role: "alert",
+            span { class: "font-semibold", "Could not load config" }
+            span { "{error}" }
+            if quarantined {
+                span { "A copy was saved to {quarantine_path}." }
+            }
+            span { "Changes won't be saved until the config is fixed or reset." }
+            div { class: "flex gap-2",
+                Button {
+                    variant: ButtonVariant::Outline,
+                    onclick: move |_| {
+                        if let Err(error) = config_service.write().reload() {
+                            consume_toast().error(
+                                "Could not load config".to_string(),
+                                ToastOptions::new().description(error.to_string()),
+                            );
+                        }
+                    },
+                    "Retry"
+                }
+                Button {
+                    variant: ButtonVariant::Destructive,
+                    onclick: move |_| config_service.write().reset(),
+                    "Reset"
+                }
+            }
+        }
+    }
+}
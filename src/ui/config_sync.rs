@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{ToastOptions, consume_toast};
+use log::error;
+use uuid::Uuid;
+
+use crate::services::{ConfigService, ConfigWatcher};
+use crate::ui::util::use_listener;
+
+/// Applies edits made to the config file outside the app, and reports failed saves
+#[component]
+pub fn ConfigSync(
+    config_service: Signal<ConfigService>,
+    selected: Signal<HashSet<Uuid>>,
+) -> Element {
+    let changed_sender = use_listener(Callback::new(move |()| {
+        if config_service.read().config().matches_file() {
+            return; // our own save
+        }
+        reload(config_service, selected);
+    }));
+    let save_error = use_memo(move || config_service.read().save_error().map(str::to_string));
+    use_effect(move || {
+        if let Some(error) = save_error() {
+            consume_toast().error(
+                "Could not save config".to_string(),
+                ToastOptions::new().description(error),
+            );
+        }
+    });
+    use_hook(move || {
+        let watcher = ConfigWatcher::new(changed_sender)
+            .inspect_err(|e| error!("could not watch config: {e}"))
+            .ok();
+        Rc::new(watcher)
+    });
+
+    rsx! {}
+}
+
+/// Reports errors as a toast, keeping the current config
+pub fn reload(mut config_service: Signal<ConfigService>, selected: Signal<HashSet<Uuid>>) {
+    if let Err(error) = config_service.write().reload() {
+        consume_toast().error(
+            "Could not reload config".to_string(),
+            ToastOptions::new().description(error.to_string()),
+        );
+        return;
+    }
+    retain_existing(config_service, selected);
+}
+
+/// Drops selected groups that no longer exist after the config was replaced
+pub fn retain_existing(config_service: Signal<ConfigService>, mut selected: Signal<HashSet<Uuid>>) {
+    let config_service = config_service.read();
+    selected
+        .write()
+        .retain(|group_id| config_service.config().group(*group_id).is_ok());
+}
@@ -2,11 +2,15 @@ use dioxus::prelude::*;
 use dioxus_primitives::toast::{ToastOptions, consume_toast};
 use uuid::Uuid;
 
+use crate::components::button::{Button, ButtonVariant};
+use crate::models::{Action, Hotkey, ReverseHotkey};
 use crate::os::{AppSelection, System};
-use crate::services::ConfigService;
+use crate::services::{ActionService, ConfigService};
+use crate::ui::app_actions::execute_reporting;
 use crate::ui::app_list::AppList;
 use crate::ui::util::{
-    EditableText, HotkeyPicker, InputMode, ListOperation, TargetPicker, use_listener,
+    CyclePicker, EditableText, GesturePicker, HotkeyPicker, InputMode, ListOperation, RepeatPicker,
+    TargetPicker, use_listener,
 };
 
 #[component]
@@ -40,9 +44,45 @@ pub fn GroupConfig(
             );
         }
     };
+    let reverse_hotkey = use_memo(move || {
+        let config_service = config_service.read();
+        config_service
+            .config()
+            .reverse_binding(group_id)
+            .ok()
+            .and_then(|(hotkey, _)| hotkey)
+    });
+    let set_reverse_hotkey = move |reverse_hotkey| {
+        let result = config_service
+            .write()
+            .set_reverse_hotkey(group_id, reverse_hotkey);
+        if let Err(error) = result {
+            consume_toast().error(
+                "Error binding hotkey".to_string(),
+                ToastOptions::new().description(error.to_string()),
+            );
+        }
+    };
     let set_target = Callback::new(move |app| {
         config_service.write().set_target(group_id, app);
     });
+    let set_cycle = Callback::new(move |cycle| {
+        config_service.write().set_cycle(group_id, cycle);
+    });
+    let set_on_repeat = Callback::new(move |on_repeat| {
+        config_service.write().set_on_repeat(group_id, on_repeat);
+    });
+    let set_gestures = Callback::new(move |gestures| {
+        config_service.write().set_gestures(group_id, gestures);
+    });
+    let action_service = use_context::<ActionService>();
+    let mut rules_refreshed = use_signal(|| 0);
+    let rules = use_memo(move || {
+        rules_refreshed(); // apps may have started or stopped since
+        group(); // rules may have changed
+        action_service.rule_matches(group_id)
+    });
+    let refresh_rules = Callback::new(move |_| rules_refreshed += 1);
     use_app_list_listener(config_service, group_id);
 
     let list_operation_tx = use_coroutine_handle::<ListOperation<Uuid>>();
@@ -73,15 +113,53 @@ pub fn GroupConfig(
                     on_commit: set_name,
                 }
                 label { r#for: "hotkey-picker", "Hotkey" }
-                HotkeyPicker { hotkey: group().hotkey, set_hotkey }
+                HotkeyPicker { id: "hotkey-picker", hotkey: group().hotkey, set_hotkey }
+                label { r#for: "reverse-hotkey-picker", "Reverse" }
+                div { class: "flex gap-2",
+                    HotkeyPicker {
+                        id: "reverse-hotkey-picker",
+                        hotkey: reverse_hotkey(),
+                        set_hotkey: move |hotkey: Option<Hotkey>| set_reverse_hotkey(hotkey.into()),
+                    }
+                    Button {
+                        variant: ButtonVariant::Ghost,
+                        disabled: group().reverse_hotkey == ReverseHotkey::Derived,
+                        title: "Use the hotkey plus Shift, unless that's taken",
+                        onclick: move |_| set_reverse_hotkey(ReverseHotkey::Derived),
+                        "Shift"
+                    }
+                }
                 label { r#for: "target-picker", "Target" }
                 TargetPicker {
-                    apps: group().apps().to_vec(),
-                    target: group().target.clone(),
+                    apps: group().apps(),
+                    target: group().target(),
                     set_target: set_target,
                 }
+                label { r#for: "cycle-picker", "Cycle" }
+                CyclePicker { cycle: group().cycle, set_cycle }
+                label { r#for: "repeat-picker", "After Last App" }
+                RepeatPicker { on_repeat: group().on_repeat, set_on_repeat }
+                label { r#for: "gesture-picker", "Gestures" }
+                GesturePicker { gestures: group().gestures, set_gestures }
             }
-            AppList { apps: group().apps().to_vec() }
+            div { class: "flex gap-2 pl-1",
+                GroupActionButton { action: Action::LaunchAllApps { group_id }, "Launch All" }
+                GroupActionButton { action: Action::QuitAllApps { group_id }, "Quit All" }
+            }
+            AppList { apps: group().apps(), rules: rules(), refresh_rules }
+        }
+    }
+}
+
+/// Runs the action right away, like its hotkey would, see `execute_reporting`
+#[component]
+fn GroupActionButton(action: Action, children: Element) -> Element {
+    let action_service = use_context::<ActionService>();
+    rsx! {
+        Button {
+            variant: ButtonVariant::Outline,
+            onclick: move |_| execute_reporting(action_service.clone(), action.clone()),
+            {children}
         }
     }
 }
@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use dioxus::desktop::window;
+use dioxus::prelude::*;
+use log::error;
+use uuid::Uuid;
+
+use crate::ipc::{self, Call, Dispatched, IpcError, Reply, Request, Response};
+use crate::models::Action;
+use crate::os;
+use crate::services::{ActionService, ConfigService, HotkeyBackend};
+use crate::ui::config_sync::retain_existing;
+use crate::ui::util::use_listener;
+
+/// Answers requests from scripts. The daemon answers those itself, and only
+/// raises the settings window it started, through a socket of its own.
+#[component]
+pub fn IpcListener(
+    config_service: Signal<ConfigService>,
+    selected: Signal<HashSet<Uuid>>,
+) -> Element {
+    let action_service = use_context::<ActionService>();
+    let from_daemon = use_context::<HotkeyBackend>() == HotkeyBackend::Daemon;
+    let call_sender = use_listener(Callback::new(move |Call { request, reply }: Call| {
+        if from_daemon && request != Request::ShowWindow {
+            let error = IpcError::InvalidRequest {
+                message: "the settings window only answers show_window".to_string(),
+            };
+            let _ = reply.send(Response::Error(error));
+            return;
+        }
+        let removes = matches!(request, Request::ReloadConfig | Request::RemoveGroup { .. });
+        match ipc::dispatch(request, &mut config_service.write()) {
+            Dispatched::Done(response) => {
+                let _ = reply.send(response);
+            }
+            Dispatched::Execute(Action::ShowGroupCtrlWindow) => {
+                window().set_visible(true);
+                window().set_focus();
+                let _ = reply.send(Response::Ok(Reply::Done));
+            }
+            Dispatched::Execute(action) => {
+                let service = action_service.clone();
+                spawn(async move {
+                    let _ = reply.send(ipc::execute(&service, &action).await);
+                });
+            }
+        }
+        if removes {
+            retain_existing(config_service, selected);
+        }
+    }));
+    use_hook(move || {
+        let socket_path = if from_daemon {
+            os::settings_socket_path()
+        } else {
+            os::socket_path()
+        };
+        ipc::serve(&socket_path, call_sender)
+            .unwrap_or_else(|e| error!("could not listen for requests: {e}"));
+    });
+
+    rsx! {}
+}
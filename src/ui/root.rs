@@ -7,11 +7,16 @@ use uuid::Uuid;
 
 use crate::components::sidebar::*;
 use crate::components::toast::ToastProvider;
-use crate::models::{Config, Hotkey, Identifiable};
+use crate::models::{Action, Config, Hotkey, Identifiable};
 use crate::os::{App, Openable};
-use crate::services::{ActionService, ConfigReader, ConfigService};
+use crate::services::{ActionService, ConfigReader, ConfigService, HotkeyBackend};
+use crate::ui::app_actions::{AppActions, reports_errors};
+use crate::ui::backup_list::BackupList;
+use crate::ui::config_error_banner::ConfigErrorBanner;
+use crate::ui::config_sync::ConfigSync;
 use crate::ui::group_config::GroupConfig;
-use crate::ui::util::{ListMenu, ListOperation, use_listener, use_selection};
+use crate::ui::ipc_listener::IpcListener;
+use crate::ui::util::{ListMenu, ListOperation, ProfilePicker, use_listener, use_selection};
 
 #[component]
 pub fn Root() -> Element {
@@ -37,6 +42,17 @@ pub fn Root() -> Element {
         }
     });
     let groups = config_service.read().config().groups().clone();
+    let profiles: Vec<(Uuid, String)> = config_service
+        .read()
+        .config()
+        .profiles()
+        .iter()
+        .map(|profile| (profile.id(), profile.name.clone()))
+        .collect();
+    let active_profile = config_service.read().config().active_profile_id();
+    let switch_profile = Callback::new(move |profile_id| {
+        config_service.write().switch_profile(profile_id);
+    });
 
     let border_pad_val = if cfg!(target_os = "macos") {
         "1px" // compensate for macOS window border
@@ -47,6 +63,9 @@ pub fn Root() -> Element {
         div {
             onmounted: move |_| window().set_decorations(true),
             ToastProvider {
+            ConfigSync { config_service, selected }
+            AppActions { config_service, selected }
+            IpcListener { config_service, selected }
             SidebarProvider {
                 Sidebar {
                     style: "padding-left: {border_pad_val};",
@@ -60,6 +79,9 @@ pub fn Root() -> Element {
                             label { r#for: "group-list", class: "pl-1", "Groups" }
                             ListMenu { selected }
                         }
+                        if !profiles.is_empty() {
+                            ProfilePicker { profiles, active: active_profile, switch_profile }
+                        }
                     }
                     SidebarContent {
                         SidebarGroup { class: "!p-0 flex-1 min-h-0",
@@ -70,6 +92,7 @@ pub fn Root() -> Element {
                                             key: "{group.id()}",
                                             group_id: group.id(),
                                             name: group.name.clone(),
+                                            enabled: config_service.read().config().is_enabled(group.id()),
                                             selected,
                                         }
                                     }
@@ -77,9 +100,13 @@ pub fn Root() -> Element {
                             }
                         }
                     }
+                    SidebarFooter { class: "!p-1",
+                        BackupList { config_service, selected }
+                    }
                 }
                 SidebarInset {
                     style: "padding-bottom: {border_pad_val}; padding-right: {border_pad_val};",
+                    ConfigErrorBanner { config_service }
                     if let Some(group_id) = active_group() {
                         GroupConfig {
                             key: "{group_id}",
@@ -96,14 +123,22 @@ pub fn Root() -> Element {
 }
 
 fn use_config_service() -> Signal<ConfigService> {
-    let config = use_hook(|| Arc::new(RwLock::new(Config::load().unwrap_or_default())));
+    let config = use_hook(|| Arc::new(RwLock::new(Config::default())));
     let config_reader = use_hook(|| ConfigReader::new(config.clone()));
     let action_service = use_hook(|| ActionService::new(config_reader.clone()));
 
     let active_recorder = use_context_provider(|| Signal::new(None::<UnboundedSender<Hotkey>>));
-    let hotkey_sender = use_listener(Callback::new(move |(hotkey, action)| {
+    let app_actions =
+        use_context_provider(|| Signal::new(None::<UnboundedSender<(Hotkey, Action)>>));
+    use_context_provider(|| action_service.clone());
+    let hotkey_sender = use_listener(Callback::new(move |(hotkey, action): (Hotkey, Action)| {
         if let Some(sender) = active_recorder() {
-            sender.unbounded_send(hotkey).unwrap();
+            // The keys alone, even if a hold or double press of them triggered it
+            sender.unbounded_send(hotkey.without_gesture()).unwrap();
+        } else if action.targets_group_ctrl() || reports_errors(&action) || hotkey.is_chord() {
+            if let Some(sender) = app_actions() {
+                sender.unbounded_send((hotkey, action)).unwrap();
+            }
         } else {
             let service = action_service.clone();
             spawn(async move {
@@ -112,11 +147,17 @@ fn use_config_service() -> Signal<ConfigService> {
         }
     }));
 
-    use_signal(|| ConfigService::new(config, hotkey_sender))
+    let hotkey_backend = use_context::<HotkeyBackend>();
+    use_signal(|| ConfigService::new(config, hotkey_sender, hotkey_backend))
 }
 
 #[component]
-fn GroupMenuItem(group_id: Uuid, name: String, selected: Signal<HashSet<Uuid>>) -> Element {
+fn GroupMenuItem(
+    group_id: Uuid,
+    name: String,
+    enabled: bool, // part of the active profile
+    selected: Signal<HashSet<Uuid>>,
+) -> Element {
     let (is_active, toggle) = use_selection(group_id, selected);
 
     rsx! {
@@ -124,7 +165,7 @@ fn GroupMenuItem(group_id: Uuid, name: String, selected: Signal<HashSet<Uuid>>)
             SidebarMenuButton {
                 is_active: is_active(),
                 onclick: move |e| toggle.call(e),
-                span { "{name}" }
+                span { class: if !enabled { "opacity-50" }, "{name}" }
             }
         }
     }
@@ -1,16 +1,24 @@
 mod app_label;
+mod cycle_picker;
 mod editable_text;
+mod gesture_picker;
 mod hotkey_picker;
 
 mod list_menu;
 mod listener;
+mod profile_picker;
+mod repeat_picker;
 mod selection;
 mod target_picker;
 
 pub use app_label::AppLabel;
+pub use cycle_picker::CyclePicker;
 pub use editable_text::{EditableText, InputMode};
+pub use gesture_picker::GesturePicker;
 pub use hotkey_picker::HotkeyPicker;
 pub use list_menu::{ListMenu, ListOperation};
 pub use listener::use_listener;
+pub use profile_picker::ProfilePicker;
+pub use repeat_picker::RepeatPicker;
 pub use selection::use_selection;
 pub use target_picker::TargetPicker;
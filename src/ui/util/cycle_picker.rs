@@ -0,0 +1,41 @@
+use dioxus::prelude::*;
+
+use crate::components::select::*;
+use crate::models::CycleOrder;
+
+const OPTIONS: [(CycleOrder, &str); 2] = [
+    (CycleOrder::Order, "Config Order"),
+    (CycleOrder::Recent, "Most Recent First"),
+];
+
+#[component]
+pub fn CyclePicker(cycle: CycleOrder, set_cycle: Callback<CycleOrder>) -> Element {
+    let value: Option<Option<CycleOrder>> = Some(Some(cycle));
+    let label = OPTIONS
+        .iter()
+        .find_map(|(option, text)| (*option == cycle).then_some(*text))
+        .unwrap_or_default();
+    rsx! {
+        div { class: "flex-1",
+        Select::<CycleOrder> {
+            value,
+            on_value_change: move |choice: Option<CycleOrder>| {
+                if let Some(choice) = choice {
+                    set_cycle.call(choice);
+                }
+            },
+            SelectTrigger { span { "{label}" } }
+            SelectList {
+                for (i, (option, text)) in OPTIONS.into_iter().enumerate() {
+                    SelectOption::<CycleOrder> {
+                        value: option,
+                        text_value: text.to_string(),
+                        index: i,
+                        "{text}"
+                    }
+                }
+            }
+        }
+        }
+    }
+}
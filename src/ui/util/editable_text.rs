@@ -3,6 +3,7 @@ use std::rc::Rc;
 use dioxus::prelude::*;
 
 use crate::components::input::Input;
+#[cfg(target_os = "macos")]
 use crate::os::{EditActions, System};
 
 #[derive(PartialEq, Clone, Copy)]
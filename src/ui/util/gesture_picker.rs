@@ -0,0 +1,40 @@
+use dioxus::prelude::*;
+
+use crate::components::select::*;
+
+const OPTIONS: [(bool, &str); 2] = [
+    (false, "Single Press Only"),
+    (true, "Double Press Opens Target, Hold Launches All"),
+];
+
+#[component]
+pub fn GesturePicker(gestures: bool, set_gestures: Callback<bool>) -> Element {
+    let value: Option<Option<bool>> = Some(Some(gestures));
+    let label = OPTIONS
+        .iter()
+        .find_map(|(option, text)| (*option == gestures).then_some(*text))
+        .unwrap_or_default();
+    rsx! {
+        div { class: "flex-1",
+        Select::<bool> {
+            value,
+            on_value_change: move |choice: Option<bool>| {
+                if let Some(choice) = choice {
+                    set_gestures.call(choice);
+                }
+            },
+            SelectTrigger { span { "{label}" } }
+            SelectList {
+                for (i, (option, text)) in OPTIONS.into_iter().enumerate() {
+                    SelectOption::<bool> {
+                        value: option,
+                        text_value: text.to_string(),
+                        index: i,
+                        "{text}"
+                    }
+                }
+            }
+        }
+        }
+    }
+}
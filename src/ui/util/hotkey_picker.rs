@@ -4,14 +4,22 @@ use dioxus::prelude::*;
 
 use crate::components::button::{Button, ButtonVariant};
 use crate::models::Hotkey;
+use crate::services::CHORD_TIMEOUT;
 use crate::ui::util::use_listener;
-use crate::util::is_modifier;
+use crate::util::{is_modifier, sleep};
 
 #[component]
-pub fn HotkeyPicker(mut hotkey: Option<Hotkey>, set_hotkey: Callback<Option<Hotkey>>) -> Element {
+pub fn HotkeyPicker(
+    #[props(into)] id: String,
+    mut hotkey: Option<Hotkey>,
+    set_hotkey: Callback<Option<Hotkey>>,
+) -> Element {
     let mut recording = use_signal(|| false);
-    use_record_registered(recording, set_hotkey);
-    let onkeydown = move |evt: KeyboardEvent| record_unregistered(recording, set_hotkey, evt);
+    let mut leader = use_signal(|| None::<Hotkey>);
+    let record = chord_recorder(recording, leader, set_hotkey);
+    use_record_registered(recording, record);
+    let onkeydown =
+        move |evt: KeyboardEvent| record_unregistered(recording, leader, set_hotkey, record, evt);
     let mut input_handle = use_signal(|| None::<Rc<MountedData>>);
     use_effect(move || {
         if let Some(handle) = input_handle() {
@@ -20,7 +28,14 @@ pub fn HotkeyPicker(mut hotkey: Option<Hotkey>, set_hotkey: Callback<Option<Hotk
         }
     });
 
-    let label = if recording() {
+    let label = if let Some(first) = leader() {
+        rsx! {
+            span { class: "gap-2 flex justify-center",
+                span { "{first}, …" }
+                span { class: "text-(--muted-text)", "Enter to keep" }
+            }
+        }
+    } else if recording() {
         rsx! {
             span { "Recording..." }
         }
@@ -44,21 +59,54 @@ pub fn HotkeyPicker(mut hotkey: Option<Hotkey>, set_hotkey: Callback<Option<Hotk
     };
     rsx! {
         Button {
+            id,
             variant,
             class: "button flex-1",
             tabindex: 0,
             onmounted: move |evt: MountedEvent| input_handle.set(Some(evt.data())),
             onclick: move |_| recording.set(true),
             onkeydown, // globally registered keys never make it here
-            onblur: move |_| recording.set(false),
+            onblur: move |_| {
+                recording.set(false);
+                leader.set(None);
+            },
             { label }
         }
     }
 }
 
+/// Waits briefly after the first keystroke, so that a second one records a chord.
+/// Meanwhile the picker shows the first keystroke as pending, see `record_unregistered`.
+fn chord_recorder(
+    mut recording: Signal<bool>,
+    mut leader: Signal<Option<Hotkey>>,
+    set_hotkey: Callback<Option<Hotkey>>,
+) -> Callback<Hotkey> {
+    Callback::new(move |hotkey: Hotkey| {
+        if let Some(first) = leader() {
+            leader.set(None);
+            set_hotkey.call(Some(first.then(hotkey)));
+            recording.set(false);
+            return;
+        }
+        leader.set(Some(hotkey));
+        spawn(async move {
+            sleep(CHORD_TIMEOUT).await;
+            if leader() == Some(hotkey) {
+                leader.set(None);
+                set_hotkey.call(Some(hotkey));
+                recording.set(false);
+            }
+        });
+    })
+}
+
+/// Escape clears the hotkey, while Enter keeps a pending first keystroke right away
 fn record_unregistered(
     mut recording: Signal<bool>,
+    mut leader: Signal<Option<Hotkey>>,
     set_hotkey: Callback<Option<Hotkey>>,
+    record: Callback<Hotkey>,
     evt: KeyboardEvent,
 ) {
     let code = evt.code();
@@ -70,20 +118,25 @@ fn record_unregistered(
         return;
     }
 
-    set_hotkey.call(if code == Code::Escape {
-        None
+    if code == Code::Escape {
+        leader.set(None); // or the pending chord timer would set it again
+        set_hotkey.call(None);
+        recording.set(false);
+    } else if code == Code::Enter
+        && evt.modifiers().is_empty()
+        && let Some(first) = leader()
+    {
+        leader.set(None);
+        set_hotkey.call(Some(first));
+        recording.set(false);
     } else {
-        Some(Hotkey::new(evt.modifiers(), code))
-    });
-    recording.set(false);
+        record.call(Hotkey::new(evt.modifiers(), code));
+    }
 }
 
-fn use_record_registered(mut recording: Signal<bool>, set_hotkey: Callback<Option<Hotkey>>) {
+fn use_record_registered(recording: Signal<bool>, record: Callback<Hotkey>) {
     let mut active_recorder = use_context::<Signal<Option<UnboundedSender<Hotkey>>>>();
-    let recorder = use_listener(Callback::new(move |hotkey| {
-        set_hotkey.call(Some(hotkey));
-        recording.set(false);
-    }));
+    let recorder = use_listener(Callback::new(move |hotkey| record.call(hotkey)));
     use_effect(move || {
         active_recorder.set(if recording() {
             Some(recorder.clone())
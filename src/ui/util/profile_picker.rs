@@ -0,0 +1,45 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+
+use crate::components::select::*;
+
+const ALL_GROUPS: &str = "All Groups";
+
+#[component]
+pub fn ProfilePicker(
+    profiles: Vec<(Uuid, String)>,
+    active: Option<Uuid>,
+    switch_profile: Callback<Option<Uuid>>,
+) -> Element {
+    let value: Option<Option<Option<Uuid>>> = Some(Some(active));
+    let options: Vec<(Option<Uuid>, String)> = std::iter::once((None, ALL_GROUPS.to_string()))
+        .chain(profiles.into_iter().map(|(id, name)| (Some(id), name)))
+        .collect();
+    let label = options
+        .iter()
+        .find_map(|(option, text)| (*option == active).then_some(text.clone()))
+        .unwrap_or_default();
+    rsx! {
+        div { class: "flex-1",
+        Select::<Option<Uuid>> {
+            value,
+            on_value_change: move |choice: Option<Option<Uuid>>| {
+                if let Some(choice) = choice {
+                    switch_profile.call(choice);
+                }
+            },
+            SelectTrigger { span { "{label}" } }
+            SelectList {
+                for (i, (option, text)) in options.into_iter().enumerate() {
+                    SelectOption::<Option<Uuid>> {
+                        value: option,
+                        text_value: text.clone(),
+                        index: i,
+                        "{text}"
+                    }
+                }
+            }
+        }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use dioxus::prelude::*;
+
+use crate::components::select::*;
+use crate::models::RepeatPolicy;
+
+const OPTIONS: [(RepeatPolicy, &str); 3] = [
+    (RepeatPolicy::Cycle, "Start Over"),
+    (RepeatPolicy::ToggleBack, "Return to Previous App"),
+    (RepeatPolicy::Hide, "Hide Group"),
+];
+
+#[component]
+pub fn RepeatPicker(on_repeat: RepeatPolicy, set_on_repeat: Callback<RepeatPolicy>) -> Element {
+    let value: Option<Option<RepeatPolicy>> = Some(Some(on_repeat));
+    let label = OPTIONS
+        .iter()
+        .find_map(|(option, text)| (*option == on_repeat).then_some(*text))
+        .unwrap_or_default();
+    rsx! {
+        div { class: "flex-1",
+        Select::<RepeatPolicy> {
+            value,
+            on_value_change: move |choice: Option<RepeatPolicy>| {
+                if let Some(choice) = choice {
+                    set_on_repeat.call(choice);
+                }
+            },
+            SelectTrigger { span { "{label}" } }
+            SelectList {
+                for (i, (option, text)) in OPTIONS.into_iter().enumerate() {
+                    SelectOption::<RepeatPolicy> {
+                        value: option,
+                        text_value: text.to_string(),
+                        index: i,
+                        "{text}"
+                    }
+                }
+            }
+        }
+        }
+    }
+}
@@ -1,3 +1,6 @@
+use std::thread;
+use std::time::Duration;
+
 use global_hotkey::hotkey::Code;
 
 pub fn capitalize(s: &str) -> String {
@@ -14,3 +17,13 @@ pub fn is_modifier(code: &Code) -> bool {
         || code_str.contains("Alt")
         || code_str.contains("Shift")
 }
+
+/// Waits on a separate thread, as the executor may be single-threaded
+pub async fn sleep(duration: Duration) {
+    let (tx, rx) = futures::channel::oneshot::channel();
+    thread::spawn(move || {
+        thread::sleep(duration);
+        let _ = tx.send(());
+    });
+    let _ = rx.await;
+}